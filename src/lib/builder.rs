@@ -0,0 +1,158 @@
+use crate::{
+    LittleBlueprint, LittleColor, LittleGroup, LittlePos, LittleStructure, LittleTile,
+    MaterialTiles,
+};
+use quartz_nbt::NbtCompound;
+
+/// 逐步构造 [`LittleGroup`]，精度默认为游戏默认的 16
+#[derive(Debug, Clone)]
+pub struct LittleGroupBuilder {
+    grid: u16,
+    children: Vec<LittleGroup>,
+    tiles: MaterialTiles,
+    structure: Option<LittleStructure>,
+    extension: Option<NbtCompound>,
+}
+
+impl Default for LittleGroupBuilder {
+    fn default() -> Self {
+        LittleGroupBuilder {
+            grid: 16,
+            children: Vec::new(),
+            tiles: MaterialTiles::new(),
+            structure: None,
+            extension: None,
+        }
+    }
+}
+
+impl LittleGroupBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn grid(mut self, grid: u16) -> Self {
+        self.grid = grid;
+        self
+    }
+
+    /// 追加一个小方块，同材质同颜色的小方块按添加顺序排列
+    pub fn add_tile(
+        mut self,
+        material: impl Into<String>,
+        color: LittleColor,
+        tile: LittleTile,
+    ) -> Self {
+        self.tiles
+            .entry(material.into())
+            .or_default()
+            .entry(color)
+            .or_default()
+            .push(tile);
+        self
+    }
+
+    pub fn add_child(mut self, child: LittleGroup) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    pub fn structure(mut self, structure: LittleStructure) -> Self {
+        self.structure = Some(structure);
+        self
+    }
+
+    pub fn extension(mut self, extension: NbtCompound) -> Self {
+        self.extension = Some(extension);
+        self
+    }
+
+    pub fn build(self) -> LittleGroup {
+        LittleGroup {
+            grid: self.grid,
+            children: self.children,
+            tiles: self.tiles,
+            structure: self.structure,
+            extension: self.extension,
+            format: Default::default(),
+        }
+    }
+}
+
+/// 由顶层组构造 [`LittleBlueprint`]，`build` 时自动计算盒子数、小方块数与包围盒
+#[derive(Debug, Clone)]
+pub struct LittleBlueprintBuilder {
+    top_group: LittleGroup,
+}
+
+impl LittleBlueprintBuilder {
+    pub fn new(top_group: LittleGroup) -> Self {
+        LittleBlueprintBuilder { top_group }
+    }
+
+    /// 计数见 [`LittleBlueprint::count_tiles`]，包围盒见 [`LittleGroup::compute_bounds`]，
+    /// 没有任何小方块时包围盒为原点处的空盒
+    pub fn build(self) -> LittleBlueprint {
+        let origin = LittlePos { x: 0, y: 0, z: 0 };
+        let mut blueprint = LittleBlueprint {
+            boxes_cnt: 0,
+            tiles_cnt: 0,
+            min_pos: origin,
+            max_pos: origin,
+            top_group: self.top_group,
+        };
+        blueprint.recompute_counts();
+        blueprint.recompute_bounds();
+        blueprint
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::sample_blueprint;
+
+    #[test]
+    fn test_builders() {
+        let white = LittleColor::try_from(-1).unwrap();
+        let tile = |v: Vec<i32>| LittleTile::try_from(v).unwrap();
+        let stone = LittleGroupBuilder::new()
+            .grid(4)
+            .structure(LittleStructure::Fixed)
+            .add_tile("minecraft:stone", white, tile(vec![3, 0, 3, 4, 1, 4]))
+            .add_tile("minecraft:stone", white, tile(vec![3, 0, 4, 4, 1, 5]))
+            .add_tile("minecraft:stone", white, tile(vec![4, 0, 3, 5, 1, 4]))
+            .add_tile("minecraft:stone", white, tile(vec![4, 0, 4, 5, 1, 5]))
+            .build();
+        let lime = LittleGroupBuilder::new()
+            .grid(4)
+            .structure(LittleStructure::Fixed)
+            .add_tile("minecraft:lime_wool", white, tile(vec![0, 0, 4, 1, 1, 5]))
+            .build();
+        let purple = LittleGroupBuilder::new()
+            .grid(4)
+            .structure(LittleStructure::Fixed)
+            .add_tile("minecraft:purple_wool", white, tile(vec![1, 0, 5, 2, 1, 6]))
+            .add_child(lime)
+            .build();
+        let red = LittleGroupBuilder::new()
+            .grid(4)
+            .structure(LittleStructure::Fixed)
+            .add_tile("minecraft:red_wool", white, tile(vec![2, 0, 6, 3, 1, 7]))
+            .add_child(purple)
+            .build();
+        let top = LittleGroupBuilder::new()
+            .grid(4)
+            .add_child(stone)
+            .add_child(red)
+            .add_tile("minecraft:white_wool", white, tile(vec![3, 0, 7, 4, 1, 8]))
+            .build();
+
+        let blueprint = LittleBlueprintBuilder::new(top).build();
+        assert_eq!(blueprint, sample_blueprint());
+
+        let empty = LittleBlueprintBuilder::new(LittleGroupBuilder::new().build()).build();
+        assert_eq!((empty.boxes_cnt, empty.tiles_cnt), (0, 0));
+        assert_eq!(empty.top_group.grid, 16);
+    }
+}
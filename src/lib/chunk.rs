@@ -0,0 +1,195 @@
+use crate::{Axis, LittleBlueprint, LittleGroup, LittlePos, LittleTile, MaterialTiles, ParseError};
+use std::collections::BTreeMap;
+
+impl LittleBlueprint {
+    /// 按 `chunk_size`（公共精度单位）把作品切分为若干子蓝图，输出 `(块坐标, 子蓝图)`，
+    /// 块坐标为单元坐标整除 `chunk_size`，按块坐标排序，空块省略。
+    ///
+    /// 每个子蓝图只有一个顶层组，精度为公共精度，包围盒为块内内容的范围；
+    /// 原有的子组层级与结构信息不保留。跨越块边界的盒子按基础包围盒裁剪为普通盒子，
+    /// 完全落在块内的变换盒保持不变
+    pub fn into_chunks(
+        &self,
+        chunk_size: [u32; 3],
+    ) -> Result<Vec<(LittlePos, LittleBlueprint)>, ParseError> {
+        if chunk_size.contains(&0) {
+            return Err(ParseError::OutOfRange("chunk_size"));
+        }
+        let size = LittlePos {
+            x: i32::try_from(chunk_size[0]).map_err(|_| ParseError::Overflow("chunk_size"))?,
+            y: i32::try_from(chunk_size[1]).map_err(|_| ParseError::Overflow("chunk_size"))?,
+            z: i32::try_from(chunk_size[2]).map_err(|_| ParseError::Overflow("chunk_size"))?,
+        };
+        let grid = self.lcm_grid()?;
+
+        let mut groups = Vec::new();
+        self.top_group.for_each_group(&mut |group| {
+            if group.grid != 0 {
+                groups.push(group);
+            }
+        });
+        let mut chunks: BTreeMap<LittlePos, MaterialTiles> = BTreeMap::new();
+        for group in groups {
+            let factor = i32::from(grid / group.grid);
+            for (mat, color_tiles) in &group.tiles {
+                for (color, tiles) in color_tiles {
+                    for tile in tiles {
+                        let tile = tile
+                            .checked_scale(factor)
+                            .ok_or(ParseError::Overflow("tile"))?;
+                        let (min_pos, max_pos) = tile.bounds();
+                        if Axis::ALL
+                            .into_iter()
+                            .any(|axis| min_pos.get(axis) >= max_pos.get(axis))
+                        {
+                            continue;
+                        }
+                        let first = chunk_of(min_pos, size);
+                        let last = chunk_of(
+                            LittlePos {
+                                x: max_pos.x - 1,
+                                y: max_pos.y - 1,
+                                z: max_pos.z - 1,
+                            },
+                            size,
+                        );
+                        let single = first == last;
+                        for z in first.z..=last.z {
+                            for y in first.y..=last.y {
+                                for x in first.x..=last.x {
+                                    let key = LittlePos { x, y, z };
+                                    let clipped = if single {
+                                        tile.clone()
+                                    } else {
+                                        clip(min_pos, max_pos, key, size)
+                                    };
+                                    chunks
+                                        .entry(key)
+                                        .or_default()
+                                        .entry(mat.clone())
+                                        .or_default()
+                                        .entry(*color)
+                                        .or_default()
+                                        .push(clipped);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(chunks
+            .into_iter()
+            .map(|(key, tiles)| (key, chunk_blueprint(grid, tiles)))
+            .collect())
+    }
+}
+
+fn chunk_of(pos: LittlePos, size: LittlePos) -> LittlePos {
+    LittlePos {
+        x: pos.x.div_euclid(size.x),
+        y: pos.y.div_euclid(size.y),
+        z: pos.z.div_euclid(size.z),
+    }
+}
+
+/// 把 `min_pos..max_pos` 裁剪到块 `key` 内
+fn clip(min_pos: LittlePos, max_pos: LittlePos, key: LittlePos, size: LittlePos) -> LittleTile {
+    let lo = LittlePos {
+        x: key.x * size.x,
+        y: key.y * size.y,
+        z: key.z * size.z,
+    };
+    let hi = LittlePos {
+        x: lo.x.saturating_add(size.x),
+        y: lo.y.saturating_add(size.y),
+        z: lo.z.saturating_add(size.z),
+    };
+    LittleTile::Box {
+        min_pos: min_pos.component_max(lo),
+        max_pos: max_pos.component_min(hi),
+    }
+}
+
+fn chunk_blueprint(grid: u16, tiles: MaterialTiles) -> LittleBlueprint {
+    let all = || tiles.values().flat_map(|c| c.values()).flatten();
+    let (min_pos, max_pos) = all()
+        .map(LittleTile::bounds)
+        .reduce(|(lo, hi), (min_pos, max_pos)| {
+            (lo.component_min(min_pos), hi.component_max(max_pos))
+        })
+        .unwrap_or((
+            LittlePos { x: 0, y: 0, z: 0 },
+            LittlePos { x: 0, y: 0, z: 0 },
+        ));
+    LittleBlueprint {
+        boxes_cnt: all().count() as u32,
+        tiles_cnt: tiles.values().map(|c| c.len()).sum::<usize>() as u32,
+        min_pos,
+        max_pos,
+        top_group: LittleGroup {
+            grid,
+            children: Vec::new(),
+            tiles,
+            structure: None,
+            extension: None,
+            format: Default::default(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::sample_blueprint;
+    use crate::{LittleColor, OccupancyMode};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_into_chunks() {
+        let blueprint = sample_blueprint();
+        let original: HashMap<LittlePos, (String, LittleColor)> = blueprint
+            .occupancy_map(OccupancyMode::Geometric)
+            .unwrap()
+            .cells
+            .into_iter()
+            .map(|(pos, (mat, color))| (pos, (mat.clone(), color)))
+            .collect();
+
+        let chunks = blueprint.into_chunks([2, 2, 2]).unwrap();
+        let mut reassembled = HashMap::new();
+        for (key, chunk) in &chunks {
+            for (pos, (mat, color)) in chunk.occupancy_map(OccupancyMode::Geometric).unwrap().cells
+            {
+                assert_eq!(chunk_of(pos, LittlePos { x: 2, y: 2, z: 2 }), *key);
+                assert!(reassembled.insert(pos, (mat.clone(), color)).is_none());
+            }
+        }
+        assert_eq!(reassembled, original);
+        assert!(chunks.iter().all(|(_, chunk)| chunk.boxes_cnt > 0));
+        assert_eq!(chunks[0].0, LittlePos { x: 0, y: 0, z: 2 });
+
+        // 跨块的盒子被拆开
+        let stone = blueprint.top_group.children[0].clone();
+        let mut merged = sample_blueprint();
+        merged.top_group.children = vec![stone];
+        merged.top_group.tiles.clear();
+        let white = LittleColor::try_from(-1).unwrap();
+        merged.top_group.children[0]
+            .tiles
+            .get_mut("minecraft:stone")
+            .unwrap()
+            .insert(
+                white,
+                vec![LittleTile::try_from(vec![3, 0, 3, 5, 1, 5]).unwrap()],
+            );
+        let chunks = merged.into_chunks([4, 4, 4]).unwrap();
+        assert_eq!(chunks.len(), 4);
+
+        assert!(matches!(
+            blueprint.into_chunks([0, 1, 1]),
+            Err(ParseError::OutOfRange("chunk_size"))
+        ));
+    }
+}
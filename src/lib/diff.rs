@@ -0,0 +1,179 @@
+use crate::occupancy::checked_lcm;
+use crate::{LittleBlueprint, LittleColor, LittlePos, Material, OccupancyMode, ParseError};
+
+/// 两个蓝图在公共精度下的逐单元格差异，各列表按坐标排序
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BlueprintDiff {
+    /// 比较所用的精度
+    pub grid: u32,
+    /// 仅在新蓝图中被占据的单元格
+    pub added: Vec<LittlePos>,
+    /// 仅在旧蓝图中被占据的单元格
+    pub removed: Vec<LittlePos>,
+    /// 两边都被占据但颜色不同的单元格：`(坐标, 旧颜色, 新颜色)`
+    pub recolored: Vec<(LittlePos, LittleColor, LittleColor)>,
+    /// 两边都被占据但材质不同的单元格：`(坐标, 旧材质, 新材质)`；
+    /// 颜色同时变化的单元格也会出现在 `recolored` 中
+    pub rematerialed: Vec<(LittlePos, Material, Material)>,
+}
+
+impl BlueprintDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.recolored.is_empty()
+            && self.rematerialed.is_empty()
+    }
+}
+
+impl LittleBlueprint {
+    /// 计算从 `self` 到 `other` 的语义差异（占用、材质与颜色），而非 NBT 字节差异；
+    /// 重叠单元格的材质与颜色取法同 [`crate::OccupancyMap`]。
+    /// 两者的公共精度超出 `u16` 或坐标换算溢出时返回 `Overflow`
+    pub fn diff(&self, other: &LittleBlueprint) -> Result<BlueprintDiff, ParseError> {
+        let grid = u32::from(checked_lcm(self.lcm_grid()?, other.lcm_grid()?)?);
+        let old = self.occupancy_at(grid, OccupancyMode::Geometric)?.cells;
+        let new = other.occupancy_at(grid, OccupancyMode::Geometric)?.cells;
+
+        let mut diff = BlueprintDiff {
+            grid,
+            ..Default::default()
+        };
+        for (pos, &(old_mat, old_color)) in &old {
+            let Some(&(new_mat, new_color)) = new.get(pos) else {
+                diff.removed.push(*pos);
+                continue;
+            };
+            if new_color != old_color {
+                diff.recolored.push((*pos, old_color, new_color));
+            }
+            if new_mat != old_mat {
+                diff.rematerialed
+                    .push((*pos, old_mat.clone(), new_mat.clone()));
+            }
+        }
+        diff.added = new
+            .keys()
+            .filter(|pos| !old.contains_key(pos))
+            .copied()
+            .collect();
+
+        diff.added.sort();
+        diff.removed.sort();
+        diff.recolored.sort_by_key(|&(pos, _, _)| pos);
+        diff.rematerialed.sort_by_key(|&(pos, _, _)| pos);
+        Ok(diff)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_data::sample_blueprint;
+    use crate::{
+        LittleBlueprintBuilder, LittleColor, LittleGroupBuilder, LittlePos, LittleTile, ParseError,
+    };
+
+    #[test]
+    fn test_diff() {
+        let old = sample_blueprint();
+        assert!(old.diff(&old).unwrap().is_empty());
+
+        let mut new = old.clone();
+        let white = LittleColor::try_from(-1).unwrap();
+        // 移除白色羊毛，在 (0, 0, 3) 新增一个石头
+        new.top_group.tiles.remove("minecraft:white_wool");
+        new.top_group
+            .tiles
+            .entry("minecraft:stone".to_string())
+            .or_default()
+            .entry(white)
+            .or_default()
+            .push(LittleTile::try_from(vec![0, 0, 3, 1, 1, 4]).unwrap());
+
+        let diff = old.diff(&new).unwrap();
+        assert_eq!(diff.grid, 4);
+        assert_eq!(diff.added, vec![LittlePos { x: 0, y: 0, z: 3 }]);
+        assert_eq!(diff.removed, vec![LittlePos { x: 3, y: 0, z: 7 }]);
+        assert!(diff.recolored.is_empty());
+
+        let back = new.diff(&old).unwrap();
+        assert_eq!(back.added, diff.removed);
+        assert_eq!(back.removed, diff.added);
+
+        let mut recolored = old.clone();
+        let red = LittleColor::from_argb(0xFFFF_0000_u32 as i32);
+        let wool = recolored
+            .top_group
+            .tiles
+            .get_mut("minecraft:white_wool")
+            .unwrap();
+        let tiles = wool.remove(&white).unwrap();
+        wool.insert(red, tiles);
+        let diff = old.diff(&recolored).unwrap();
+        assert_eq!(
+            diff.recolored,
+            vec![(LittlePos { x: 3, y: 0, z: 7 }, white, red)]
+        );
+        assert!(diff.rematerialed.is_empty());
+
+        // 颜色不变、只换材质：石头换成羊毛
+        let mut rematerialed = old.clone();
+        let wool = rematerialed
+            .top_group
+            .tiles
+            .remove("minecraft:white_wool")
+            .unwrap();
+        rematerialed
+            .top_group
+            .tiles
+            .insert("minecraft:stone".to_string(), wool);
+        let swapped = old.diff(&rematerialed).unwrap();
+        assert!(swapped.recolored.is_empty());
+        assert_eq!(
+            swapped.rematerialed,
+            vec![(
+                LittlePos { x: 3, y: 0, z: 7 },
+                "minecraft:white_wool".to_string(),
+                "minecraft:stone".to_string()
+            )]
+        );
+        assert!(!swapped.is_empty());
+
+        // 与白色羊毛重叠的小方块：按材质名排序后靠后的胜出，与哈希表顺序无关
+        let mut overlapped = old.clone();
+        let blue = LittleColor::try_from(65535).unwrap();
+        for (mat, color) in [
+            ("minecraft:yellow_wool", red),
+            ("minecraft:blue_wool", blue),
+        ] {
+            overlapped
+                .top_group
+                .tiles
+                .entry(mat.to_string())
+                .or_default()
+                .insert(
+                    color,
+                    vec![LittleTile::try_from(vec![3, 0, 7, 4, 1, 8]).unwrap()],
+                );
+        }
+        let overlap = old.diff(&overlapped).unwrap();
+        assert_eq!(overlap.recolored, diff.recolored);
+        assert_eq!(
+            overlap.rematerialed,
+            vec![(
+                LittlePos { x: 3, y: 0, z: 7 },
+                "minecraft:white_wool".to_string(),
+                "minecraft:yellow_wool".to_string()
+            )]
+        );
+
+        // 两边各自的精度都合法，但合在一起超出 u16
+        let grid = |grid| {
+            LittleBlueprintBuilder::new(LittleGroupBuilder::new().grid(grid).build()).build()
+        };
+        assert!(matches!(
+            grid(65535).diff(&grid(65534)),
+            Err(ParseError::Overflow("grid"))
+        ));
+    }
+}
@@ -0,0 +1,320 @@
+use crate::{
+    LittleBlueprint, LittleColor, LittleGroup, LittlePos, LittleTile, Material, ParseError,
+};
+
+/// 一次可精确撤销的编辑，`path` 为从顶层组依次进入的子组下标
+#[derive(Debug, Clone, PartialEq)]
+pub enum EditOp {
+    /// 小方块追加在该颜色列表末尾；`new_material`/`new_color` 表示插入时新建了对应分组
+    InsertTile {
+        path: Vec<usize>,
+        material: Material,
+        color: LittleColor,
+        tile: LittleTile,
+        new_material: bool,
+        new_color: bool,
+    },
+    /// 记录被删除的小方块及其下标，撤销时原样放回；删除后留下的空分组保持不变
+    RemoveTile {
+        path: Vec<usize>,
+        material: Material,
+        color: LittleColor,
+        index: usize,
+        tile: LittleTile,
+    },
+    /// 以该组精度为单位的位移，撤销时反向平移
+    Translate { path: Vec<usize>, delta: LittlePos },
+    /// `from` 的 `count` 个小方块被追加到 `to` 末尾，`new_color` 表示 `to` 原本不存在
+    Recolor {
+        path: Vec<usize>,
+        material: Material,
+        from: LittleColor,
+        to: LittleColor,
+        count: usize,
+        new_color: bool,
+    },
+}
+
+/// 编辑记录，按执行顺序保存 [`EditOp`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EditLog {
+    ops: Vec<EditOp>,
+}
+
+impl EditLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn ops(&self) -> &[EditOp] {
+        &self.ops
+    }
+
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+}
+
+impl LittleGroup {
+    /// 按子组下标路径查找后代组，空路径为自身
+    pub fn group_at_mut(&mut self, path: &[usize]) -> Option<&mut LittleGroup> {
+        path.iter()
+            .try_fold(self, |group, &i| group.children.get_mut(i))
+    }
+}
+
+impl LittleBlueprint {
+    fn group_mut(&mut self, path: &[usize]) -> Result<&mut LittleGroup, ParseError> {
+        self.top_group
+            .group_at_mut(path)
+            .ok_or(ParseError::NotFound("group"))
+    }
+
+    /// 在 `path` 处的组追加一个小方块并记录
+    pub fn insert_tile(
+        &mut self,
+        log: &mut EditLog,
+        path: &[usize],
+        material: &str,
+        color: LittleColor,
+        tile: LittleTile,
+    ) -> Result<(), ParseError> {
+        let group = self.group_mut(path)?;
+        let new_material = !group.tiles.contains_key(material);
+        let color_tiles = group.tiles.entry(material.to_owned()).or_default();
+        let new_color = !color_tiles.contains_key(&color);
+        color_tiles.entry(color).or_default().push(tile.clone());
+        log.ops.push(EditOp::InsertTile {
+            path: path.to_vec(),
+            material: material.to_owned(),
+            color,
+            tile,
+            new_material,
+            new_color,
+        });
+        Ok(())
+    }
+
+    /// 删除 `path` 处组中指定颜色列表的第 `index` 个小方块并记录，返回被删除的小方块
+    pub fn remove_tile(
+        &mut self,
+        log: &mut EditLog,
+        path: &[usize],
+        material: &str,
+        color: LittleColor,
+        index: usize,
+    ) -> Result<LittleTile, ParseError> {
+        let tiles = self
+            .group_mut(path)?
+            .tiles
+            .get_mut(material)
+            .ok_or(ParseError::NotFound("material"))?
+            .get_mut(&color)
+            .ok_or(ParseError::NotFound("color"))?;
+        if index >= tiles.len() {
+            return Err(ParseError::NotFound("tile"));
+        }
+        let tile = tiles.remove(index);
+        log.ops.push(EditOp::RemoveTile {
+            path: path.to_vec(),
+            material: material.to_owned(),
+            color,
+            index,
+            tile: tile.clone(),
+        });
+        Ok(tile)
+    }
+
+    /// 平移 `path` 处的组（见 [`LittleGroup::translate`]）并记录
+    pub fn translate_group(
+        &mut self,
+        log: &mut EditLog,
+        path: &[usize],
+        delta: LittlePos,
+    ) -> Result<(), ParseError> {
+        // 预先确认反向位移可表示，保证撤销不会失败
+        delta.checked_neg().ok_or(ParseError::Overflow("delta"))?;
+        self.group_mut(path)?.translate(delta)?;
+        log.ops.push(EditOp::Translate {
+            path: path.to_vec(),
+            delta,
+        });
+        Ok(())
+    }
+
+    /// 把 `path` 处组中某材质的 `from` 颜色整体改为 `to` 并记录；两者相同时不做任何事
+    pub fn recolor(
+        &mut self,
+        log: &mut EditLog,
+        path: &[usize],
+        material: &str,
+        from: LittleColor,
+        to: LittleColor,
+    ) -> Result<(), ParseError> {
+        let color_tiles = self
+            .group_mut(path)?
+            .tiles
+            .get_mut(material)
+            .ok_or(ParseError::NotFound("material"))?;
+        if !color_tiles.contains_key(&from) {
+            return Err(ParseError::NotFound("color"));
+        }
+        if from == to {
+            return Ok(());
+        }
+        let moved = color_tiles.remove(&from).unwrap_or_default();
+        let new_color = !color_tiles.contains_key(&to);
+        let count = moved.len();
+        color_tiles.entry(to).or_default().extend(moved);
+        log.ops.push(EditOp::Recolor {
+            path: path.to_vec(),
+            material: material.to_owned(),
+            from,
+            to,
+            count,
+            new_color,
+        });
+        Ok(())
+    }
+
+    /// 撤销 `log` 中最后一次编辑，返回被撤销的操作；记录为空时返回 None。
+    /// 编辑之后若绕过记录直接修改过蓝图，撤销可能失败，此时操作保留在记录中
+    pub fn undo(&mut self, log: &mut EditLog) -> Result<Option<EditOp>, ParseError> {
+        let Some(op) = log.ops.last() else {
+            return Ok(None);
+        };
+        match op {
+            EditOp::InsertTile {
+                path,
+                material,
+                color,
+                new_material,
+                new_color,
+                ..
+            } => {
+                let group = self.group_mut(path)?;
+                let color_tiles = group
+                    .tiles
+                    .get_mut(material)
+                    .ok_or(ParseError::NotFound("material"))?;
+                color_tiles
+                    .get_mut(color)
+                    .and_then(|tiles| tiles.pop())
+                    .ok_or(ParseError::NotFound("tile"))?;
+                if *new_color {
+                    color_tiles.remove(color);
+                }
+                if *new_material {
+                    group.tiles.remove(material);
+                }
+            }
+            EditOp::RemoveTile {
+                path,
+                material,
+                color,
+                index,
+                tile,
+            } => {
+                let tiles = self
+                    .group_mut(path)?
+                    .tiles
+                    .get_mut(material)
+                    .ok_or(ParseError::NotFound("material"))?
+                    .get_mut(color)
+                    .ok_or(ParseError::NotFound("color"))?;
+                if *index > tiles.len() {
+                    return Err(ParseError::NotFound("tile"));
+                }
+                tiles.insert(*index, tile.clone());
+            }
+            EditOp::Translate { path, delta } => {
+                let back = delta.checked_neg().ok_or(ParseError::Overflow("delta"))?;
+                self.group_mut(path)?.translate(back)?;
+            }
+            EditOp::Recolor {
+                path,
+                material,
+                from,
+                to,
+                count,
+                new_color,
+            } => {
+                let color_tiles = self
+                    .group_mut(path)?
+                    .tiles
+                    .get_mut(material)
+                    .ok_or(ParseError::NotFound("material"))?;
+                let tiles = color_tiles
+                    .get_mut(to)
+                    .ok_or(ParseError::NotFound("color"))?;
+                let start = tiles
+                    .len()
+                    .checked_sub(*count)
+                    .ok_or(ParseError::NotFound("tile"))?;
+                let moved = tiles.split_off(start);
+                if *new_color {
+                    color_tiles.remove(to);
+                }
+                color_tiles.insert(*from, moved);
+            }
+        }
+        Ok(log.ops.pop())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::sample_blueprint;
+
+    #[test]
+    fn test_undo() {
+        let original = sample_blueprint();
+        let mut blueprint = original.clone();
+        let mut log = EditLog::new();
+        let white = LittleColor::try_from(-1).unwrap();
+        let red = LittleColor::try_from(-16776961).unwrap();
+        let tile = LittleTile::try_from(vec![0, 0, 0, 1, 1, 1]).unwrap();
+
+        blueprint
+            .insert_tile(&mut log, &[1], "minecraft:glass", red, tile.clone())
+            .unwrap();
+        assert_ne!(blueprint, original);
+        blueprint.undo(&mut log).unwrap();
+        assert_eq!(blueprint, original);
+        assert!(log.is_empty());
+
+        blueprint
+            .insert_tile(&mut log, &[], "minecraft:white_wool", white, tile)
+            .unwrap();
+        let removed = blueprint
+            .remove_tile(&mut log, &[0], "minecraft:stone", white, 1)
+            .unwrap();
+        assert_eq!(removed.bounds().0, LittlePos { x: 3, y: 0, z: 4 });
+        blueprint
+            .translate_group(&mut log, &[1, 0], LittlePos { x: 2, y: 0, z: -1 })
+            .unwrap();
+        blueprint
+            .recolor(&mut log, &[0], "minecraft:stone", white, red)
+            .unwrap();
+        assert_eq!(log.len(), 4);
+
+        let snapshot = blueprint.clone();
+        assert!(matches!(
+            blueprint.remove_tile(&mut log, &[5], "minecraft:stone", red, 0),
+            Err(ParseError::NotFound("group"))
+        ));
+        assert_eq!(blueprint, snapshot);
+
+        while blueprint.undo(&mut log).unwrap().is_some() {}
+        assert_eq!(blueprint, original);
+        assert_eq!(
+            blueprint.top_group.children[0].tiles["minecraft:stone"][&white][1],
+            removed
+        );
+    }
+}
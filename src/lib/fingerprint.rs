@@ -0,0 +1,100 @@
+use crate::{LittleColor, LittleGroup, Material};
+use std::collections::BTreeMap;
+
+/// 小方块在组树中的稳定标识：子组下标路径、材质、颜色，以及在该颜色列表中的下标
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TileId {
+    pub path: Vec<usize>,
+    pub material: Material,
+    pub color: LittleColor,
+    pub index: usize,
+}
+
+// FNV-1a，保证指纹在不同进程与编译器版本间稳定
+fn fnv1a(words: &[i32]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for word in words {
+        for byte in word.to_le_bytes() {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    hash
+}
+
+impl LittleGroup {
+    /// 为每个小方块计算基于其编码数据的稳定指纹，变换前后各取一次即可增量比对
+    pub fn fingerprint_tiles(&self) -> BTreeMap<TileId, u64> {
+        let mut fingerprints = BTreeMap::new();
+        self.collect_fingerprints(&mut Vec::new(), &mut fingerprints);
+        fingerprints
+    }
+
+    fn collect_fingerprints(&self, path: &mut Vec<usize>, out: &mut BTreeMap<TileId, u64>) {
+        for (material, color_tiles) in &self.tiles {
+            for (color, tiles) in color_tiles {
+                for (index, tile) in tiles.iter().enumerate() {
+                    let words: Vec<i32> = tile.clone().try_into().unwrap_or_default();
+                    let id = TileId {
+                        path: path.clone(),
+                        material: material.clone(),
+                        color: *color,
+                        index,
+                    };
+                    out.insert(id, fnv1a(&words));
+                }
+            }
+        }
+        for (i, child) in self.children.iter().enumerate() {
+            path.push(i);
+            child.collect_fingerprints(path, out);
+            path.pop();
+        }
+    }
+
+    /// 比对两次 [`LittleGroup::fingerprint_tiles`] 的结果，返回新增、删除或内容改变的小方块
+    pub fn changed_tiles(
+        before: &BTreeMap<TileId, u64>,
+        after: &BTreeMap<TileId, u64>,
+    ) -> Vec<TileId> {
+        let mut changed: Vec<TileId> = after
+            .iter()
+            .filter(|&(id, hash)| before.get(id) != Some(hash))
+            .map(|(id, _)| id.clone())
+            .collect();
+        changed.extend(before.keys().filter(|id| !after.contains_key(*id)).cloned());
+        changed.sort();
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::sample_blueprint;
+    use crate::{LittlePos, LittleTile};
+
+    #[test]
+    fn test_changed_tiles() {
+        let mut group = sample_blueprint().top_group;
+        let before = group.fingerprint_tiles();
+        assert_eq!(before.len(), 8);
+        assert_eq!(group.fingerprint_tiles(), before);
+        assert!(LittleGroup::changed_tiles(&before, &before).is_empty());
+
+        for (mat, _, tile) in group.iter_tiles_mut() {
+            if mat == "minecraft:purple_wool" {
+                *tile = LittleTile::try_from(vec![1, 0, 5, 2, 2, 6]).unwrap();
+            }
+        }
+        let after = group.fingerprint_tiles();
+        let changed = LittleGroup::changed_tiles(&before, &after);
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].path, vec![1, 0]);
+        assert_eq!(changed[0].material, "minecraft:purple_wool");
+
+        group.translate(LittlePos { x: 1, y: 0, z: 0 }).unwrap();
+        let moved = group.fingerprint_tiles();
+        assert_eq!(LittleGroup::changed_tiles(&before, &moved).len(), 8);
+    }
+}
@@ -0,0 +1,81 @@
+use crate::{LittleGroup, LittlePos, ParseError};
+use quartz_nbt::NbtCompound;
+use std::rc::Rc;
+
+/// 可被多个实例共享的组数据
+#[derive(Debug, Clone, PartialEq)]
+pub struct LittleGroupRef(pub Rc<LittleGroup>);
+
+/// 组实例：引用一份共享组，并按 `offset` 平移（以共享组自身的精度为单位）
+#[derive(Debug, Clone, PartialEq)]
+pub struct LittleInstance {
+    pub group: LittleGroupRef,
+    pub offset: LittlePos,
+}
+
+/// 带实例化子组的组，用于在内存中紧凑表示大量重复的子结构。
+/// NBT 格式没有实例化概念，序列化时每个实例都会展开为一个普通子组
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstancedGroup {
+    pub group: LittleGroup,
+    pub instances: Vec<LittleInstance>,
+}
+
+impl InstancedGroup {
+    /// 展开为普通组：实例依次追加在已有子组之后
+    pub fn flatten(&self) -> Result<LittleGroup, ParseError> {
+        let mut group = self.group.clone();
+        for instance in &self.instances {
+            let mut child = LittleGroup::clone(&instance.group.0);
+            child.translate(instance.offset)?;
+            group.children.push(child);
+        }
+        Ok(group)
+    }
+}
+
+impl TryInto<NbtCompound> for InstancedGroup {
+    type Error = ParseError;
+
+    fn try_into(self) -> Result<NbtCompound, Self::Error> {
+        LittleGroup::try_into(self.flatten()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::sample_blueprint;
+
+    #[test]
+    fn test_flatten_instances() {
+        let blueprint = sample_blueprint();
+        let stone = LittleGroupRef(Rc::new(blueprint.top_group.children[0].clone()));
+        let mut root = blueprint.top_group.clone();
+        root.children.clear();
+        let instanced = InstancedGroup {
+            group: root.clone(),
+            instances: vec![
+                LittleInstance {
+                    group: stone.clone(),
+                    offset: LittlePos { x: 0, y: 0, z: 0 },
+                },
+                LittleInstance {
+                    group: stone.clone(),
+                    offset: LittlePos { x: 0, y: 1, z: 0 },
+                },
+            ],
+        };
+        assert_eq!(Rc::strong_count(&stone.0), 3);
+
+        let flat = instanced.flatten().expect("Failed to flatten");
+        assert_eq!(flat.children.len(), 2);
+        assert_eq!(flat.children[0], *stone.0);
+        let mut lifted = LittleGroup::clone(&stone.0);
+        lifted.translate(LittlePos { x: 0, y: 1, z: 0 }).unwrap();
+        assert_eq!(flat.children[1], lifted);
+
+        let nbt: NbtCompound = instanced.try_into().expect("Failed to serialize");
+        assert_eq!(LittleGroup::try_from(nbt).unwrap(), flat);
+    }
+}
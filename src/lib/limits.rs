@@ -0,0 +1,122 @@
+use crate::{LittleBlueprint, LittleGroup};
+
+/// LittleTiles 游戏内放置限制，默认值偏保守，可按服务器配置调整
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameLimits {
+    /// 小方块总数上限：与游戏一致，每个组内的每种「材质 + 颜色」计为一个小方块，
+    /// 无论包含多少个盒子
+    pub max_tiles: usize,
+    /// 包围盒每个轴向的最大尺寸，单位为方块
+    pub max_size: u32,
+    /// 组嵌套的最大深度，顶层组计为 1
+    pub max_depth: usize,
+    /// 允许的最大精度
+    pub max_grid: u16,
+}
+
+impl Default for GameLimits {
+    fn default() -> Self {
+        GameLimits {
+            max_tiles: 65536,
+            max_size: 64,
+            max_depth: 32,
+            max_grid: 32,
+        }
+    }
+}
+
+impl LittleGroup {
+    /// 嵌套深度：没有子组时为 1
+    pub fn depth(&self) -> usize {
+        1 + self
+            .children
+            .iter()
+            .map(LittleGroup::depth)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl LittleBlueprint {
+    /// 检查蓝图能否在游戏内放置，返回全部违反项的描述
+    pub fn check_game_limits(&self, limits: &GameLimits) -> Result<(), Vec<String>> {
+        let mut violations = Vec::new();
+
+        let mut tiles = 0;
+        let mut bad_grids = Vec::new();
+        self.top_group.for_each_group(&mut |group| {
+            tiles += group.tiles.values().map(|c| c.len()).sum::<usize>();
+            if (!group.grid.is_power_of_two() || group.grid > limits.max_grid)
+                && !bad_grids.contains(&group.grid)
+            {
+                bad_grids.push(group.grid);
+            }
+        });
+        if tiles > limits.max_tiles {
+            violations.push(format!(
+                "too many tiles: {tiles} (limit {})",
+                limits.max_tiles
+            ));
+        }
+        for grid in bad_grids {
+            violations.push(format!(
+                "grid {grid} is not a power of two up to {}",
+                limits.max_grid
+            ));
+        }
+
+        // 包围盒以顶层精度存储，换算为方块数（向上取整）
+        let grid = i64::from(self.top_group.grid.max(1));
+        let size = [
+            i64::from(self.max_pos.x) - i64::from(self.min_pos.x),
+            i64::from(self.max_pos.y) - i64::from(self.min_pos.y),
+            i64::from(self.max_pos.z) - i64::from(self.min_pos.z),
+        ];
+        for (name, extent) in ["x", "y", "z"].into_iter().zip(size) {
+            let blocks = (extent + grid - 1).div_euclid(grid);
+            if blocks > i64::from(limits.max_size) {
+                violations.push(format!(
+                    "size along {name} is {blocks} blocks (limit {})",
+                    limits.max_size
+                ));
+            }
+        }
+
+        let depth = self.top_group.depth();
+        if depth > limits.max_depth {
+            violations.push(format!(
+                "nesting depth {depth} (limit {})",
+                limits.max_depth
+            ));
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::sample_blueprint;
+
+    #[test]
+    fn test_check_game_limits() {
+        let blueprint = sample_blueprint();
+        assert_eq!(blueprint.top_group.depth(), 4);
+        assert!(blueprint.check_game_limits(&GameLimits::default()).is_ok());
+
+        let tight = GameLimits {
+            max_tiles: 4,
+            max_size: 1,
+            max_depth: 2,
+            max_grid: 2,
+        };
+        let violations = blueprint.check_game_limits(&tight).unwrap_err();
+        assert_eq!(violations.len(), 5, "{violations:?}");
+        assert!(violations[0].starts_with("too many tiles: 5"));
+    }
+}
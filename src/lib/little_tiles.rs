@@ -7,27 +7,187 @@ use std::{collections::HashMap, hash::Hash};
 #[derive(Debug)]
 pub enum ParseError {
     InvalidFormat,
+    /// 缺少必需的 NBT 字段
+    MissingField(&'static str),
+    /// NBT 字段的类型不对，`expected` 为期望的类型
+    WrongType {
+        field: String,
+        expected: &'static str,
+    },
+    /// 数组字段的长度不对
+    BadArrayLength {
+        field: String,
+        expected: usize,
+        got: usize,
+    },
+    /// 变换盒的翻转与角点偏移数据不完整
+    InvalidTransformData,
+    /// 坐标换算时整数溢出，附带出错的字段
+    Overflow(&'static str),
+    /// 字段取值超出允许范围，例如负的尺寸
+    OutOfRange(&'static str),
+    /// 编辑时找不到指定的组、材质、颜色或小方块
+    NotFound(&'static str),
+    /// 坐标无法在两个精度之间无损换算
+    IncompatibleGrid {
+        grid: u16,
+        target: u16,
+    },
+    /// 某个材质列表中第 `index` 个元素解析失败
+    InvalidTile {
+        material: String,
+        index: usize,
+        source: Box<ParseError>,
+    },
+    /// 十六进制颜色字符串长度不对或含有非十六进制字符，附带原字符串
+    InvalidHexColor(String),
+    /// 读写二进制 NBT 失败
+    Io(quartz_nbt::io::NbtIoError),
 }
 
 impl std::fmt::Display for ParseError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ParseError::InvalidFormat => write!(f, "Invalid SNBT format"),
+            ParseError::MissingField(field) => write!(f, "Missing field `{field}`"),
+            ParseError::WrongType { field, expected } => {
+                write!(f, "Field `{field}` should be {expected}")
+            }
+            ParseError::BadArrayLength {
+                field,
+                expected,
+                got,
+            } => write!(
+                f,
+                "Field `{field}` should have {expected} elements, got {got}"
+            ),
+            ParseError::InvalidTransformData => write!(f, "Truncated transformable box data"),
+            ParseError::Overflow(field) => write!(f, "Integer overflow in `{field}`"),
+            ParseError::OutOfRange(field) => write!(f, "Value out of range in `{field}`"),
+            ParseError::NotFound(what) => write!(f, "No such {what}"),
+            ParseError::IncompatibleGrid { grid, target } => {
+                write!(f, "Cannot convert grid {grid} to grid {target} losslessly")
+            }
+            ParseError::InvalidTile {
+                material,
+                index,
+                source,
+            } => write!(
+                f,
+                "Invalid entry #{index} of material `{material}`: {source}"
+            ),
+            ParseError::InvalidHexColor(hex) => write!(
+                f,
+                "Invalid hex color `{hex}`: expected #RRGGBB or #RRGGBBAA"
+            ),
+            ParseError::Io(err) => write!(f, "NBT I/O error: {err}"),
         }
     }
 }
 
-impl std::error::Error for ParseError {}
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseError::InvalidTile { source, .. } => Some(source.as_ref()),
+            ParseError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
 
 /// 坐标
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct LittlePos {
     pub x: i32,
     pub y: i32,
     pub z: i32,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+impl LittlePos {
+    /// 三个分量同乘 `factor`，溢出时返回 None
+    pub(crate) fn checked_scale(self, factor: i32) -> Option<LittlePos> {
+        Some(LittlePos {
+            x: self.x.checked_mul(factor)?,
+            y: self.y.checked_mul(factor)?,
+            z: self.z.checked_mul(factor)?,
+        })
+    }
+
+    /// 沿 `facing` 方向相邻的坐标
+    pub fn offset(self, facing: Facing) -> LittlePos {
+        let n = facing.normal();
+        LittlePos {
+            x: self.x + n.x,
+            y: self.y + n.y,
+            z: self.z + n.z,
+        }
+    }
+
+    /// 逐分量相加，溢出时返回 None
+    pub(crate) fn checked_add(self, other: LittlePos) -> Option<LittlePos> {
+        Some(LittlePos {
+            x: self.x.checked_add(other.x)?,
+            y: self.y.checked_add(other.y)?,
+            z: self.z.checked_add(other.z)?,
+        })
+    }
+
+    /// 三个分量取反，溢出时返回 None
+    pub(crate) fn checked_neg(self) -> Option<LittlePos> {
+        Some(LittlePos {
+            x: self.x.checked_neg()?,
+            y: self.y.checked_neg()?,
+            z: self.z.checked_neg()?,
+        })
+    }
+
+    /// 逐分量取较小值
+    pub(crate) fn component_min(self, other: LittlePos) -> LittlePos {
+        LittlePos {
+            x: self.x.min(other.x),
+            y: self.y.min(other.y),
+            z: self.z.min(other.z),
+        }
+    }
+
+    /// 逐分量取较大值
+    pub(crate) fn component_max(self, other: LittlePos) -> LittlePos {
+        LittlePos {
+            x: self.x.max(other.x),
+            y: self.y.max(other.y),
+            z: self.z.max(other.z),
+        }
+    }
+
+    /// 取指定轴上的分量
+    pub fn get(self, axis: Axis) -> i32 {
+        match axis {
+            Axis::X => self.x,
+            Axis::Y => self.y,
+            Axis::Z => self.z,
+        }
+    }
+
+    /// 取指定轴上分量的可变引用
+    pub fn get_mut(&mut self, axis: Axis) -> &mut i32 {
+        match axis {
+            Axis::X => &mut self.x,
+            Axis::Y => &mut self.y,
+            Axis::Z => &mut self.z,
+        }
+    }
+}
+
+impl std::fmt::Display for LittlePos {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+/// 颜色。NBT 中以单个 i32 存储，默认打包顺序为 RGBA：
+/// `r` 在最高字节（bit 24..32），随后 `g`、`b`，`a` 在最低字节（bit 0..8）。
+/// Minecraft 常用的 ARGB 顺序见 [`LittleColor::from_argb`] / [`LittleColor::to_argb`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, PartialOrd, Ord)]
 pub struct LittleColor {
     pub r: u8,
     pub g: u8,
@@ -35,6 +195,54 @@ pub struct LittleColor {
     pub a: u8,
 }
 
+impl LittleColor {
+    /// 按 ARGB 顺序解包：`a` 在最高字节，随后依次为 `r`、`g`、`b`
+    pub fn from_argb(value: i32) -> LittleColor {
+        let c = value as u32;
+        LittleColor {
+            a: (c >> 24) as u8,
+            r: (c >> 16) as u8,
+            g: (c >> 8) as u8,
+            b: c as u8,
+        }
+    }
+
+    /// 按 ARGB 顺序打包，与 [`LittleColor::from_argb`] 互逆
+    pub fn to_argb(self) -> i32 {
+        (((self.a as u32) << 24)
+            | ((self.r as u32) << 16)
+            | ((self.g as u32) << 8)
+            | (self.b as u32)) as i32
+    }
+
+    /// 解析 `#RRGGBB` 或 `#RRGGBBAA`（`#` 可省略），省略透明度时为不透明
+    pub fn from_hex(hex: &str) -> Result<LittleColor, ParseError> {
+        let invalid = || ParseError::InvalidHexColor(hex.to_string());
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        if !matches!(digits.len(), 6 | 8) || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        let channel = |i: usize| {
+            digits
+                .get(2 * i..2 * i + 2)
+                .map_or(Ok(255), |byte| u8::from_str_radix(byte, 16))
+                .map_err(|_| invalid())
+        };
+        Ok(LittleColor {
+            r: channel(0)?,
+            g: channel(1)?,
+            b: channel(2)?,
+            a: channel(3)?,
+        })
+    }
+
+    /// 输出 `#RRGGBBAA`，总是包含透明度，与 [`LittleColor::from_hex`] 互逆
+    pub fn to_hex(&self) -> String {
+        format!("#{:02X}{:02X}{:02X}{:02X}", self.r, self.g, self.b, self.a)
+    }
+}
+
+/// 按 RGBA 顺序解包
 impl TryFrom<i32> for LittleColor {
     type Error = ParseError;
 
@@ -49,6 +257,7 @@ impl TryFrom<i32> for LittleColor {
     }
 }
 
+/// 按 RGBA 顺序打包
 impl TryInto<i32> for LittleColor {
     type Error = ParseError;
 
@@ -71,6 +280,30 @@ pub enum Facing {
     East,
 }
 
+impl Facing {
+    pub const ALL: [Facing; 6] = [
+        Facing::Down,
+        Facing::Up,
+        Facing::North,
+        Facing::South,
+        Facing::West,
+        Facing::East,
+    ];
+
+    /// 单位法向量，Minecraft 约定：东 +X、上 +Y、南 +Z
+    pub fn normal(self) -> LittlePos {
+        let (x, y, z) = match self {
+            Facing::Down => (0, -1, 0),
+            Facing::Up => (0, 1, 0),
+            Facing::North => (0, 0, -1),
+            Facing::South => (0, 0, 1),
+            Facing::West => (-1, 0, 0),
+            Facing::East => (1, 0, 0),
+        };
+        LittlePos { x, y, z }
+    }
+}
+
 /// 立方体的 8 个角
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Enum)]
 pub enum BoxCorner {
@@ -95,6 +328,33 @@ const CORNER_ORDER: [BoxCorner; 8] = [
     BoxCorner::WDS,
 ];
 
+impl BoxCorner {
+    /// 各轴上是否位于正方向一侧（东、上、南）
+    fn sides(self) -> EnumMap<Axis, bool> {
+        let (east, up, south) = match self {
+            BoxCorner::EUN => (true, true, false),
+            BoxCorner::EUS => (true, true, true),
+            BoxCorner::EDN => (true, false, false),
+            BoxCorner::EDS => (true, false, true),
+            BoxCorner::WUN => (false, true, false),
+            BoxCorner::WUS => (false, true, true),
+            BoxCorner::WDN => (false, false, false),
+            BoxCorner::WDS => (false, false, true),
+        };
+        enum_map! { Axis::X => east, Axis::Y => up, Axis::Z => south }
+    }
+
+    /// 沿 `axis` 镜像后对应的角
+    fn mirrored(self, axis: Axis) -> BoxCorner {
+        let mut sides = self.sides();
+        sides[axis] = !sides[axis];
+        CORNER_ORDER
+            .into_iter()
+            .find(|corner| corner.sides() == sides)
+            .expect("every side combination is a corner")
+    }
+}
+
 /// 坐标轴枚举：X/Y/Z
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Enum)]
 pub enum Axis {
@@ -103,22 +363,29 @@ pub enum Axis {
     Z,
 }
 
+impl Axis {
+    pub const ALL: [Axis; 3] = [Axis::X, Axis::Y, Axis::Z];
+}
+
 bitflags! {
     /// 反转坐标轴
     #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     pub struct Flipped: u8 {
-        const EAST  = 0b0000_01;
-        const WEST  = 0b0000_10;
-        const SOUTH = 0b0001_00;
-        const NORTH = 0b0010_00;
-        const UP    = 0b0100_00;
-        const DOWN  = 0b1000_00;
+        const EAST  = 0b00_0001;
+        const WEST  = 0b00_0010;
+        const SOUTH = 0b00_0100;
+        const NORTH = 0b00_1000;
+        const UP    = 0b01_0000;
+        const DOWN  = 0b10_0000;
     }
 }
 
 /// 角落偏移量 8 * 3 = 24
 type CornerOffsets = EnumMap<BoxCorner, EnumMap<Axis, i16>>;
 
+/// 小方块坐标允许偏离原点的最大方块数，超出视为损坏数据
+const MAX_TILE_BLOCKS: i32 = 1024;
+
 /// Main tile enum
 #[derive(Debug, Clone, PartialEq)]
 pub enum LittleTile {
@@ -134,24 +401,267 @@ pub enum LittleTile {
     },
 }
 
-fn get_int_field(nbt: &NbtCompound, field: &str) -> Result<i32, ParseError> {
+impl LittleTile {
+    /// 基础包围盒 `(min_pos, max_pos)`，不含变换盒的角点偏移
+    pub fn bounds(&self) -> (LittlePos, LittlePos) {
+        match self {
+            LittleTile::Box { min_pos, max_pos }
+            | LittleTile::TransformableBox {
+                min_pos, max_pos, ..
+            } => (*min_pos, *max_pos),
+        }
+    }
+
+    pub(crate) fn bounds_mut(&mut self) -> (&mut LittlePos, &mut LittlePos) {
+        match self {
+            LittleTile::Box { min_pos, max_pos }
+            | LittleTile::TransformableBox {
+                min_pos, max_pos, ..
+            } => (min_pos, max_pos),
+        }
+    }
+
+    /// 每个轴上 `min <= max`，且坐标不超出原点 `MAX_TILE_BLOCKS` 个方块（按 `grid` 换算）
+    pub fn is_valid(&self, grid: u16) -> bool {
+        let limit = i32::from(grid) * MAX_TILE_BLOCKS;
+        let (min_pos, max_pos) = self.bounds();
+        grid != 0
+            && Axis::ALL.into_iter().all(|axis| {
+                let (lo, hi) = (min_pos.get(axis), max_pos.get(axis));
+                lo <= hi && -limit <= lo && hi <= limit
+            })
+    }
+
+    /// 修正为 [`LittleTile::is_valid`] 认可的范围：先交换颠倒的 `min`/`max`，再截断越界坐标
+    pub fn clamp_to_grid(&mut self, grid: u16) {
+        let limit = i32::from(grid) * MAX_TILE_BLOCKS;
+        self.normalize();
+        let (min_pos, max_pos) = self.bounds_mut();
+        for axis in Axis::ALL {
+            let lo = min_pos.get_mut(axis);
+            *lo = (*lo).clamp(-limit, limit);
+            let hi = max_pos.get_mut(axis);
+            *hi = (*hi).clamp(-limit, limit);
+        }
+    }
+
+    /// 每个轴上都满足 `min <= max`
+    pub fn is_normalized(&self) -> bool {
+        let (min_pos, max_pos) = self.bounds();
+        Axis::ALL
+            .into_iter()
+            .all(|axis| min_pos.get(axis) <= max_pos.get(axis))
+    }
+
+    /// 基础包围盒覆盖的单元格，`max` 不包含在内；未规范化的盒子视为空
+    pub fn cells(&self) -> impl Iterator<Item = LittlePos> {
+        let (min_pos, max_pos) = self.bounds();
+        let max_pos = if self.is_normalized() {
+            max_pos
+        } else {
+            min_pos
+        };
+        (min_pos.z..max_pos.z).flat_map(move |z| {
+            (min_pos.y..max_pos.y)
+                .flat_map(move |y| (min_pos.x..max_pos.x).map(move |x| LittlePos { x, y, z }))
+        })
+    }
+
+    /// 基础包围盒的单元格数，未规范化的盒子为 0
+    pub fn volume(&self) -> u64 {
+        if !self.is_normalized() {
+            return 0;
+        }
+        let (min_pos, max_pos) = self.bounds();
+        Axis::ALL
+            .into_iter()
+            .map(|axis| (i64::from(max_pos.get(axis)) - i64::from(min_pos.get(axis))) as u64)
+            .product()
+    }
+
+    /// 实际形状的包围盒：变换盒取 8 个角点加上偏移后的范围，可能超出或小于基础包围盒
+    pub fn shape_bounds(&self) -> (LittlePos, LittlePos) {
+        let (min_pos, max_pos) = self.bounds();
+        let LittleTile::TransformableBox { corner, .. } = self else {
+            return (min_pos, max_pos);
+        };
+        CORNER_ORDER
+            .iter()
+            .map(|&c| {
+                let sides = c.sides();
+                let at = |axis: Axis| {
+                    let base = if sides[axis] {
+                        max_pos.get(axis)
+                    } else {
+                        min_pos.get(axis)
+                    };
+                    base.saturating_add(i32::from(corner[c][axis]))
+                };
+                LittlePos {
+                    x: at(Axis::X),
+                    y: at(Axis::Y),
+                    z: at(Axis::Z),
+                }
+            })
+            .fold((max_pos, min_pos), |(lo, hi), pos| {
+                (lo.component_min(pos), hi.component_max(pos))
+            })
+    }
+
+    /// 沿 `axis` 以 `reflect / 2` 为镜面镜像（坐标变为 `reflect - v`），`min`/`max` 保持有序；
+    /// 变换盒的角点及其偏移、该轴两侧的翻转标记随之互换。溢出时返回 None
+    pub(crate) fn mirrored(&self, axis: Axis, reflect: i32) -> Option<LittleTile> {
+        let mut tile = self.clone();
+        let (min_pos, max_pos) = tile.bounds_mut();
+        let (lo, hi) = (min_pos.get(axis), max_pos.get(axis));
+        *min_pos.get_mut(axis) = reflect.checked_sub(hi)?;
+        *max_pos.get_mut(axis) = reflect.checked_sub(lo)?;
+        if let LittleTile::TransformableBox { flips, corner, .. } = &mut tile {
+            let original = *corner;
+            for c in CORNER_ORDER {
+                let mut offsets = original[c];
+                offsets[axis] = offsets[axis].checked_neg()?;
+                corner[c.mirrored(axis)] = offsets;
+            }
+            let pair = match axis {
+                Axis::X => Flipped::EAST | Flipped::WEST,
+                Axis::Y => Flipped::UP | Flipped::DOWN,
+                Axis::Z => Flipped::SOUTH | Flipped::NORTH,
+            };
+            // 只有一侧被翻转时才需要交换
+            if flips.intersects(pair) && !flips.contains(pair) {
+                flips.toggle(pair);
+            }
+        }
+        Some(tile)
+    }
+
+    /// 逐轴交换颠倒的 `min`/`max`，角点偏移保持不变
+    fn normalize(&mut self) {
+        let (min_pos, max_pos) = self.bounds_mut();
+        for axis in Axis::ALL {
+            let (lo, hi) = (min_pos.get_mut(axis), max_pos.get_mut(axis));
+            if *lo > *hi {
+                std::mem::swap(lo, hi);
+            }
+        }
+    }
+
+    /// 在公共精度下比较几何形状，`self` 位于 `self_grid`，`other` 位于 `other_grid`。
+    /// 两个精度不成整数倍关系（或缩放溢出）时返回 false
+    pub fn geom_eq(&self, self_grid: u16, other: &LittleTile, other_grid: u16) -> bool {
+        if self_grid == 0 || other_grid == 0 {
+            return false;
+        }
+        let grid = self_grid.max(other_grid);
+        if !grid.is_multiple_of(self_grid) || !grid.is_multiple_of(other_grid) {
+            return false;
+        }
+        let lhs = self.checked_scale(i32::from(grid / self_grid));
+        let rhs = other.checked_scale(i32::from(grid / other_grid));
+        match (lhs, rhs) {
+            (Some(lhs), Some(rhs)) => lhs == rhs,
+            _ => false,
+        }
+    }
+
+    /// 将包围盒与角点偏移整体放大 `factor` 倍，溢出时返回 None
+    pub(crate) fn checked_scale(&self, factor: i32) -> Option<LittleTile> {
+        match self {
+            LittleTile::Box { min_pos, max_pos } => Some(LittleTile::Box {
+                min_pos: min_pos.checked_scale(factor)?,
+                max_pos: max_pos.checked_scale(factor)?,
+            }),
+            LittleTile::TransformableBox {
+                min_pos,
+                max_pos,
+                flips,
+                corner,
+            } => {
+                let mut scaled = *corner;
+                for offsets in scaled.values_mut() {
+                    for offset in offsets.values_mut() {
+                        let value = i32::from(*offset).checked_mul(factor)?;
+                        *offset = i16::try_from(value).ok()?;
+                    }
+                }
+                Some(LittleTile::TransformableBox {
+                    min_pos: min_pos.checked_scale(factor)?,
+                    max_pos: max_pos.checked_scale(factor)?,
+                    flips: *flips,
+                    corner: scaled,
+                })
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for LittleTile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LittleTile::Box { min_pos, max_pos } => write!(f, "Box[{min_pos}..{max_pos}]"),
+            LittleTile::TransformableBox {
+                min_pos,
+                max_pos,
+                flips,
+                corner,
+            } => {
+                write!(f, "TBox[{min_pos}..{max_pos}")?;
+                if !flips.is_empty() {
+                    let names: Vec<&str> = flips.iter_names().map(|(name, _)| name).collect();
+                    write!(f, ", flips: {}", names.join("|"))?;
+                }
+                // 只统计非零偏移的数量，完整数据仍可通过 Debug 查看
+                let moved = corner
+                    .values()
+                    .flat_map(|axes| axes.values())
+                    .filter(|offset| **offset != 0)
+                    .count();
+                if moved > 0 {
+                    write!(f, ", offsets: {moved}")?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+fn wrong_type(field: impl Into<String>, expected: &'static str) -> ParseError {
+    ParseError::WrongType {
+        field: field.into(),
+        expected,
+    }
+}
+
+fn get_int_field(nbt: &NbtCompound, field: &'static str) -> Result<i32, ParseError> {
     match nbt.inner().get(field) {
         Some(NbtTag::Int(value)) => Ok(*value),
-        _ => Err(ParseError::InvalidFormat),
+        Some(_) => Err(wrong_type(field, "Int")),
+        None => Err(ParseError::MissingField(field)),
     }
 }
 
-fn get_int_array(nbt: &NbtCompound, field: &str) -> Result<Vec<i32>, ParseError> {
+// 兼容 `[I; ...]` 与由 Int 组成的普通列表两种写法
+fn get_int_array(nbt: &NbtCompound, field: &'static str) -> Result<Vec<i32>, ParseError> {
     match nbt.inner().get(field) {
         Some(NbtTag::IntArray(value)) => Ok(value.clone()),
-        _ => Err(ParseError::InvalidFormat),
+        Some(NbtTag::List(list)) => list
+            .iter()
+            .enumerate()
+            .map(|(i, tag)| match tag {
+                NbtTag::Int(value) => Ok(*value),
+                _ => Err(wrong_type(format!("{field}[{i}]"), "Int")),
+            })
+            .collect(),
+        Some(_) => Err(wrong_type(field, "IntArray")),
+        None => Err(ParseError::MissingField(field)),
     }
 }
 
 // 解析变换数据
 fn decode_transformable_data(data: &[i32]) -> Result<(Flipped, CornerOffsets), ParseError> {
     if data.is_empty() {
-        return Err(ParseError::InvalidFormat);
+        return Err(ParseError::InvalidTransformData);
     }
     // 计算Flipped位
     let flags_bits = data[0] as u32;
@@ -167,12 +677,13 @@ fn decode_transformable_data(data: &[i32]) -> Result<(Flipped, CornerOffsets), P
         vals.push((u & 0xFFFF) as i16);
     }
     let mut vi = 0;
-    for (ax_i, &axis) in [Axis::X, Axis::Y, Axis::Z].iter().enumerate() {
-        for (corner_i, &corner) in CORNER_ORDER.iter().enumerate() {
+    // 偏移量按“角优先、轴次之”的顺序打包，与标志位 3 * corner + axis 一致
+    for (corner_i, &corner) in CORNER_ORDER.iter().enumerate() {
+        for (ax_i, &axis) in [Axis::X, Axis::Y, Axis::Z].iter().enumerate() {
             let bit = 3 * corner_i + ax_i;
             if ((flags_bits) >> bit) & 0x1 == 1 {
                 if vi >= vals.len() {
-                    return Err(ParseError::InvalidFormat);
+                    return Err(ParseError::InvalidTransformData);
                 }
                 corner_offsets[corner][axis] = vals[vi];
                 vi += 1;
@@ -249,13 +760,18 @@ impl TryFrom<Vec<i32>> for LittleTile {
             Some((min_pos, max_pos, rest))
         }
 
+        let bad_length = || ParseError::BadArrayLength {
+            field: "tile".to_string(),
+            expected: 6,
+            got: arr.len(),
+        };
         match arr.len() {
             6 => {
-                let (min_pos, max_pos, _) = split_bbox(arr).ok_or(ParseError::InvalidFormat)?;
+                let (min_pos, max_pos, _) = split_bbox(arr).ok_or_else(bad_length)?;
                 Ok(LittleTile::Box { min_pos, max_pos })
             }
             n if n >= 7 => {
-                let (min_pos, max_pos, rest) = split_bbox(arr).ok_or(ParseError::InvalidFormat)?;
+                let (min_pos, max_pos, rest) = split_bbox(arr).ok_or_else(bad_length)?;
                 let (flips, corner) = decode_transformable_data(rest)?;
                 Ok(LittleTile::TransformableBox {
                     min_pos,
@@ -264,7 +780,7 @@ impl TryFrom<Vec<i32>> for LittleTile {
                     corner,
                 })
             }
-            _ => Err(ParseError::InvalidFormat),
+            _ => Err(bad_length()),
         }
     }
 }
@@ -297,85 +813,353 @@ impl TryInto<Vec<i32>> for LittleTile {
     }
 }
 
-type ColorTiles = HashMap<LittleColor, Vec<LittleTile>>;
-type Material = String;
+pub type ColorTiles = HashMap<LittleColor, Vec<LittleTile>>;
+pub type Material = String;
+
+pub type MaterialTiles = HashMap<Material, ColorTiles>;
+
+/// 解析时记录的原始排版信息，序列化时尽量按原样还原
+///
+/// 不参与 [`LittleGroup`] 的相等比较
+#[derive(Debug, Clone, Default)]
+pub(crate) struct GroupFormat {
+    /// 每种材质的颜色标记序列：(颜色, 该标记之后的小方块数)
+    runs: HashMap<Material, Vec<(LittleColor, usize)>>,
+    /// `grid` 字段原本的数值类型
+    grid_tag: GridTag,
+}
 
-type MaterialTiles = HashMap<Material, ColorTiles>;
+/// `grid` 可能以 Short、Int 或 Long 存储，默认写出 Int
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum GridTag {
+    Short,
+    #[default]
+    Int,
+    Long,
+}
 
+/// 组的结构信息（NBT 中的 `s`），种类由其中的 `id` 决定
 #[derive(Debug, Clone, PartialEq)]
+pub enum LittleStructure {
+    /// 只有 `id: "fixed"` 的固定结构
+    Fixed,
+    /// 其它结构（如 `chair`、`door`、`bed`），`data` 保存除 `id` 外的全部字段，原样写回
+    Other { id: String, data: NbtCompound },
+}
+
+impl LittleStructure {
+    pub fn id(&self) -> &str {
+        match self {
+            LittleStructure::Fixed => "fixed",
+            LittleStructure::Other { id, .. } => id,
+        }
+    }
+}
+
+impl TryFrom<NbtCompound> for LittleStructure {
+    type Error = ParseError;
+
+    fn try_from(mut data: NbtCompound) -> Result<Self, Self::Error> {
+        let id = match data.inner_mut().remove("id") {
+            Some(NbtTag::String(id)) => id,
+            Some(_) => return Err(wrong_type("s.id", "String")),
+            None => return Err(ParseError::MissingField("id")),
+        };
+        if id == "fixed" && data.is_empty() {
+            return Ok(LittleStructure::Fixed);
+        }
+        Ok(LittleStructure::Other { id, data })
+    }
+}
+
+impl TryInto<NbtCompound> for LittleStructure {
+    type Error = ParseError;
+
+    fn try_into(self) -> Result<NbtCompound, Self::Error> {
+        let (id, mut nbt) = match self {
+            LittleStructure::Fixed => ("fixed".to_string(), NbtCompound::new()),
+            LittleStructure::Other { id, data } => (id, data),
+        };
+        nbt.insert("id", id);
+        Ok(nbt)
+    }
+}
+
+/// 一个组：精度、小方块、子组，以及可选的结构与扩展数据。
+/// 在库外请用 [`crate::LittleGroupBuilder`] 或解析得到
+///
+/// `==` 不比较解析时记录的排版信息（颜色标记顺序、`grid` 的数值类型），
+/// 因此相等的两个组序列化结果可能不同
+#[derive(Debug, Clone)]
 pub struct LittleGroup {
     pub grid: u16,
     pub children: Vec<LittleGroup>,
     pub tiles: MaterialTiles,
-    pub structure: Option<NbtCompound>,
+    pub structure: Option<LittleStructure>,
     pub extension: Option<NbtCompound>,
+    pub(crate) format: GroupFormat,
+}
+
+impl PartialEq for LittleGroup {
+    fn eq(&self, other: &Self) -> bool {
+        self.grid == other.grid
+            && self.children == other.children
+            && self.tiles == other.tiles
+            && self.structure == other.structure
+            && self.extension == other.extension
+    }
+}
+
+// 记录的标记序列与当前小方块数量一致时才可用于还原
+fn runs_match(runs: &[(LittleColor, usize)], color_tiles: &ColorTiles) -> bool {
+    let mut counts: HashMap<LittleColor, usize> = HashMap::new();
+    for (color, count) in runs {
+        *counts.entry(*color).or_default() += count;
+    }
+    color_tiles
+        .iter()
+        .all(|(color, tiles)| counts.get(color).copied().unwrap_or(0) == tiles.len())
+        && counts
+            .iter()
+            .all(|(color, count)| *count == 0 || color_tiles.contains_key(color))
+}
+
+impl LittleGroup {
+    /// 本组及所有后代组小方块实际形状（含变换盒角点偏移）的紧包围盒，以本组精度为单位；
+    /// 精度不同的子组按比例换算，不能整除时向外取整。没有任何小方块时返回 None
+    pub fn compute_bounds(&self) -> Option<(LittlePos, LittlePos)> {
+        let own = self
+            .tiles
+            .values()
+            .flat_map(|color_tiles| color_tiles.values().flatten())
+            .map(LittleTile::shape_bounds);
+        let nested = self.children.iter().filter_map(|child| {
+            let (min_pos, max_pos) = child.compute_bounds()?;
+            if child.grid == self.grid || child.grid == 0 || self.grid == 0 {
+                return Some((min_pos, max_pos));
+            }
+            let (grid, target) = (i64::from(child.grid), i64::from(self.grid));
+            let convert = |pos: LittlePos, round_up: bool| {
+                let scale = |v: i32| {
+                    let scaled = i64::from(v) * target;
+                    let v = if round_up {
+                        scaled.div_euclid(grid) + i64::from(scaled.rem_euclid(grid) != 0)
+                    } else {
+                        scaled.div_euclid(grid)
+                    };
+                    v.clamp(i64::from(i32::MIN), i64::from(i32::MAX)) as i32
+                };
+                LittlePos {
+                    x: scale(pos.x),
+                    y: scale(pos.y),
+                    z: scale(pos.z),
+                }
+            };
+            Some((convert(min_pos, false), convert(max_pos, true)))
+        });
+        own.chain(nested).reduce(|(lo, hi), (min_pos, max_pos)| {
+            (lo.component_min(min_pos), hi.component_max(max_pos))
+        })
+    }
+
+    /// 修正本组及所有后代组中 `min > max` 的盒子（逐轴交换），返回修正的数量
+    pub fn normalize_boxes(&mut self) -> usize {
+        let mut fixed = 0;
+        for (_, _, tile) in self.iter_tiles_mut() {
+            if !tile.is_normalized() {
+                tile.normalize();
+                fixed += 1;
+            }
+        }
+        fixed
+    }
+
+    /// 按文件中的颜色标记顺序列出本组小方块，重复出现的标记各占一项
+    ///
+    /// 若没有解析记录，或小方块已被修改导致记录失效，则每种颜色一项并按颜色排序
+    pub fn tiles_ordered(&self) -> HashMap<&str, Vec<(LittleColor, &[LittleTile])>> {
+        self.tiles
+            .iter()
+            .map(|(mat, color_tiles)| {
+                let ordered = match self.format.runs.get(mat) {
+                    Some(runs) if runs_match(runs, color_tiles) => {
+                        let mut consumed: HashMap<LittleColor, usize> = HashMap::new();
+                        runs.iter()
+                            .map(|&(color, count)| {
+                                let start = consumed.entry(color).or_default();
+                                let tiles = color_tiles
+                                    .get(&color)
+                                    .map_or(&[][..], |t| &t[*start..*start + count]);
+                                *start += count;
+                                (color, tiles)
+                            })
+                            .collect()
+                    }
+                    _ => {
+                        let mut canonical: Vec<(LittleColor, &[LittleTile])> = color_tiles
+                            .iter()
+                            .map(|(color, tiles)| (*color, tiles.as_slice()))
+                            .collect();
+                        canonical.sort_by_key(|(color, _)| *color);
+                        canonical
+                    }
+                };
+                (mat.as_str(), ordered)
+            })
+            .collect()
+    }
+
+    /// 遍历本组及所有后代组的小方块：先本组，再按顺序深度优先进入子组
+    pub fn iter_tiles(&self) -> impl Iterator<Item = (&Material, &LittleColor, &LittleTile)> {
+        let own = self.tiles.iter().flat_map(|(mat, color_tiles)| {
+            color_tiles
+                .iter()
+                .flat_map(move |(color, tiles)| tiles.iter().map(move |tile| (mat, color, tile)))
+        });
+        let nested = self.children.iter().flat_map(
+            |child| -> Box<dyn Iterator<Item = (&Material, &LittleColor, &LittleTile)> + '_> {
+                Box::new(child.iter_tiles())
+            },
+        );
+        own.chain(nested)
+    }
+
+    /// 可变遍历本组及所有后代组的小方块：先本组，再按顺序深度优先进入子组
+    pub fn iter_tiles_mut(
+        &mut self,
+    ) -> impl Iterator<Item = (&Material, &LittleColor, &mut LittleTile)> {
+        // 解构以分别借用 tiles 与 children
+        let LittleGroup {
+            tiles, children, ..
+        } = self;
+        let own = tiles.iter_mut().flat_map(|(mat, color_tiles)| {
+            color_tiles.iter_mut().flat_map(move |(color, tiles)| {
+                tiles.iter_mut().map(move |tile| (mat, &*color, tile))
+            })
+        });
+        let nested = children.iter_mut().flat_map(
+            |child| -> Box<dyn Iterator<Item = (&Material, &LittleColor, &mut LittleTile)> + '_> {
+                Box::new(child.iter_tiles_mut())
+            },
+        );
+        own.chain(nested)
+    }
+}
+
+// 宽松模式下记录错误并继续，严格模式下直接返回错误
+fn recover(errors: &mut Option<&mut Vec<ParseError>>, err: ParseError) -> Result<(), ParseError> {
+    match errors {
+        Some(errors) => {
+            errors.push(err);
+            Ok(())
+        }
+        None => Err(err),
+    }
 }
 
 impl TryFrom<NbtCompound> for LittleGroup {
     type Error = ParseError;
 
     fn try_from(nbt: NbtCompound) -> Result<Self, Self::Error> {
+        LittleGroup::parse(nbt, None)
+    }
+}
+
+impl LittleGroup {
+    /// `errors` 为 Some 时跳过损坏的子组与小方块并记录错误，组自身的必需字段损坏时仍返回错误
+    fn parse(
+        nbt: NbtCompound,
+        mut errors: Option<&mut Vec<ParseError>>,
+    ) -> Result<Self, ParseError> {
         let mut map: HashMap<String, NbtTag> = nbt.into_inner();
 
         // 解析精度
-        let Some(NbtTag::Int(grid)) = map.remove("grid") else {
-            return Err(ParseError::InvalidFormat);
+        let (grid, grid_tag) = match map.remove("grid") {
+            Some(NbtTag::Short(grid)) => (i64::from(grid), GridTag::Short),
+            Some(NbtTag::Int(grid)) => (i64::from(grid), GridTag::Int),
+            Some(NbtTag::Long(grid)) => (grid, GridTag::Long),
+            Some(_) => return Err(wrong_type("grid", "Short, Int or Long")),
+            None => return Err(ParseError::MissingField("grid")),
         };
-        let grid = grid as u16;
+        let grid = u16::try_from(grid).map_err(|_| ParseError::OutOfRange("grid"))?;
 
         // 解析子组
         let mut children = Vec::new();
         let clist = match map.remove("c") {
             Some(NbtTag::List(list)) => list.into_inner(),
             None => Vec::new(),
-            _ => return Err(ParseError::InvalidFormat),
+            _ => return Err(wrong_type("c", "List")),
         };
-        for item in clist {
+        for (i, item) in clist.into_iter().enumerate() {
             let NbtTag::Compound(child) = item else {
-                return Err(ParseError::InvalidFormat);
+                recover(&mut errors, wrong_type(format!("c[{i}]"), "Compound"))?;
+                continue;
             };
-            children.push(LittleGroup::try_from(child)?);
+            match LittleGroup::parse(child, errors.as_deref_mut()) {
+                Ok(child) => children.push(child),
+                Err(err) => recover(&mut errors, err)?,
+            }
         }
 
         // 解析结构体
         let structure = match map.remove("s") {
-            Some(NbtTag::Compound(c)) => Some(c),
+            Some(NbtTag::Compound(c)) => Some(LittleStructure::try_from(c)?),
             None => None,
-            _ => return Err(ParseError::InvalidFormat),
+            _ => return Err(wrong_type("s", "Compound")),
         };
 
         // 解析扩展
         let extension = match map.remove("e") {
             Some(NbtTag::Compound(c)) => Some(c),
             None => None,
-            _ => return Err(ParseError::InvalidFormat),
+            _ => return Err(wrong_type("e", "Compound")),
         };
 
         // 解析小方块
         let mut tiles: MaterialTiles = MaterialTiles::new();
-        let Some(NbtTag::Compound(mt)) = map.remove("t") else {
-            return Err(ParseError::InvalidFormat);
+        let mut runs = HashMap::new();
+        let mt = match map.remove("t") {
+            Some(NbtTag::Compound(mt)) => mt,
+            Some(_) => return Err(wrong_type("t", "Compound")),
+            None => return Err(ParseError::MissingField("t")),
         };
         for (mat, tag) in mt.into_inner() {
             let NbtTag::List(flat_list) = tag else {
-                return Err(ParseError::InvalidFormat);
+                return Err(wrong_type(format!("t.{mat}"), "List"));
             };
             let mut color_tiles: ColorTiles = HashMap::new();
-            let mut cur_color = LittleColor::default();
-            for tag in flat_list.into_inner() {
+            let mut mat_runs: Vec<(LittleColor, usize)> = Vec::new();
+            for (index, tag) in flat_list.into_inner().into_iter().enumerate() {
+                let context = |source| ParseError::InvalidTile {
+                    material: mat.clone(),
+                    index,
+                    source: Box::new(source),
+                };
                 match tag {
-                    NbtTag::IntArray(ar) if ar.len() == 1 => {
-                        cur_color = LittleColor::try_from(ar[0])?;
-                    }
+                    NbtTag::IntArray(ar) if ar.len() == 1 => match LittleColor::try_from(ar[0]) {
+                        Ok(color) => mat_runs.push((color, 0)),
+                        Err(err) => recover(&mut errors, context(err))?,
+                    },
                     NbtTag::IntArray(ar) => {
-                        let tile = LittleTile::try_from(ar)?;
-                        color_tiles.entry(cur_color).or_default().push(tile);
-                    }
-                    _ => {
-                        return Err(ParseError::InvalidFormat);
+                        let tile = match LittleTile::try_from(ar) {
+                            Ok(tile) => tile,
+                            Err(err) => {
+                                recover(&mut errors, context(err))?;
+                                continue;
+                            }
+                        };
+                        // 首个标记之前的小方块使用默认颜色
+                        if mat_runs.is_empty() {
+                            mat_runs.push((LittleColor::default(), 0));
+                        }
+                        let (cur_color, count) = mat_runs.last_mut().unwrap();
+                        *count += 1;
+                        color_tiles.entry(*cur_color).or_default().push(tile);
                     }
+                    _ => recover(&mut errors, context(wrong_type("tile", "IntArray")))?,
                 }
             }
+            runs.insert(mat.clone(), mat_runs);
             tiles.insert(mat.clone(), color_tiles);
         }
 
@@ -385,6 +1169,7 @@ impl TryFrom<NbtCompound> for LittleGroup {
             tiles,
             structure,
             extension,
+            format: GroupFormat { runs, grid_tag },
         })
     }
 }
@@ -392,23 +1177,28 @@ impl TryFrom<NbtCompound> for LittleGroup {
 impl TryInto<NbtCompound> for LittleGroup {
     type Error = ParseError;
 
-    fn try_into(self) -> Result<NbtCompound, Self::Error> {
+    fn try_into(mut self) -> Result<NbtCompound, Self::Error> {
         let mut nbt = NbtCompound::new();
 
         // grid
-        nbt.insert("grid", self.grid as i32);
+        match self.format.grid_tag {
+            // 超出 i16 的精度无法按 Short 写回，退回 Int
+            GridTag::Short if self.grid <= i16::MAX as u16 => nbt.insert("grid", self.grid as i16),
+            GridTag::Long => nbt.insert("grid", i64::from(self.grid)),
+            _ => nbt.insert("grid", i32::from(self.grid)),
+        }
 
         // children list
         let mut clist = Vec::new();
-        for child in self.children {
+        for child in std::mem::take(&mut self.children) {
             let child_nbt = LittleGroup::try_into(child)?;
             clist.push(NbtTag::Compound(child_nbt));
         }
         nbt.insert("c", NbtTag::List(NbtList::from(clist)));
 
         // optional structure
-        if let Some(ref struct_c) = self.structure {
-            nbt.insert("s", NbtTag::Compound(struct_c.clone()));
+        if let Some(structure) = self.structure.take() {
+            nbt.insert("s", NbtTag::Compound(structure.try_into()?));
         }
 
         // optional extension
@@ -418,11 +1208,11 @@ impl TryInto<NbtCompound> for LittleGroup {
 
         // tiles by material
         let mut mt = NbtCompound::new();
-        for (mat, color_tiles) in &self.tiles {
+        for (mat, ordered) in self.tiles_ordered() {
             let mut flat = Vec::new();
-            for (color, tiles) in color_tiles {
+            for (color, tiles) in ordered {
                 // color marker
-                let c_val: i32 = (*color).try_into()?;
+                let c_val: i32 = color.try_into()?;
                 flat.push(NbtTag::IntArray(vec![c_val]));
 
                 // each tile array
@@ -431,7 +1221,7 @@ impl TryInto<NbtCompound> for LittleGroup {
                     flat.push(NbtTag::IntArray(arr));
                 }
             }
-            mt.insert(mat.clone(), NbtTag::List(NbtList::from(flat)));
+            mt.insert(mat, NbtTag::List(NbtList::from(flat)));
         }
         nbt.insert("t", NbtTag::Compound(mt));
 
@@ -452,25 +1242,108 @@ impl TryFrom<NbtCompound> for LittleBlueprint {
     type Error = ParseError;
 
     fn try_from(root: NbtCompound) -> Result<Self, Self::Error> {
+        LittleBlueprint::parse(root, None)
+    }
+}
+
+impl LittleBlueprint {
+    /// 尽力解析：跳过损坏的小方块与子组，返回解析结果和被跳过部分的错误。
+    /// 蓝图头部或顶层组的必需字段损坏时仍返回错误
+    pub fn try_from_lossy(root: NbtCompound) -> Result<(Self, Vec<ParseError>), ParseError> {
+        let mut errors = Vec::new();
+        let blueprint = LittleBlueprint::parse(root, Some(&mut errors))?;
+        Ok((blueprint, errors))
+    }
+
+    /// 按顶层组的 [`LittleGroup::compute_bounds`] 更新 `min_pos`/`max_pos`，
+    /// 没有任何小方块时保持不变
+    pub fn recompute_bounds(&mut self) {
+        if let Some((min_pos, max_pos)) = self.top_group.compute_bounds() {
+            self.min_pos = min_pos;
+            self.max_pos = max_pos;
+        }
+    }
+
+    /// 稠密网格所需的定义域 `(原点, 尺寸)`，以顶层精度为单位：原点为包围盒最小角减去 `padding`，
+    /// 尺寸为包围盒尺寸加上两侧各 `padding`，便于为膨胀等操作预留边距。超出范围时饱和
+    pub fn grid_dimensions(&self, padding: u32) -> (LittlePos, [u32; 3]) {
+        let pad = i64::from(padding);
+        let clamp_i32 = |v: i64| v.clamp(i64::from(i32::MIN), i64::from(i32::MAX)) as i32;
+        let origin = LittlePos {
+            x: clamp_i32(i64::from(self.min_pos.x) - pad),
+            y: clamp_i32(i64::from(self.min_pos.y) - pad),
+            z: clamp_i32(i64::from(self.min_pos.z) - pad),
+        };
+        let dims = Axis::ALL.map(|axis| {
+            let size = i64::from(self.max_pos.get(axis)) - i64::from(self.min_pos.get(axis));
+            (size.max(0) + 2 * pad).min(i64::from(u32::MAX)) as u32
+        });
+        (origin, dims)
+    }
+
+    /// 按整棵组树的实际内容统计 `(boxes, tiles)`，与游戏的计数方式一致：
+    /// 每个盒子（普通盒子或变换盒）计为一个 box，变换盒的角点偏移不会增加数量；
+    /// 每个组内的每种「材质 + 颜色」计为一个 tile，无论包含多少个盒子
+    pub fn count_tiles(&self) -> (u32, u32) {
+        let (mut boxes, mut tiles) = (0, 0);
+        self.top_group.for_each_group(&mut |group| {
+            for color_tiles in group.tiles.values() {
+                tiles += color_tiles.len();
+                boxes += color_tiles.values().map(Vec::len).sum::<usize>();
+            }
+        });
+        (boxes as u32, tiles as u32)
+    }
+
+    /// 用 [`LittleBlueprint::count_tiles`] 的结果更新 `boxes_cnt`/`tiles_cnt`
+    pub fn recompute_counts(&mut self) {
+        (self.boxes_cnt, self.tiles_cnt) = self.count_tiles();
+    }
+
+    /// 遍历整棵组树的小方块，见 [`LittleGroup::iter_tiles`]
+    pub fn iter_tiles(&self) -> impl Iterator<Item = (&Material, &LittleColor, &LittleTile)> {
+        self.top_group.iter_tiles()
+    }
+
+    /// 可变遍历整棵组树的小方块，见 [`LittleGroup::iter_tiles_mut`]
+    pub fn iter_tiles_mut(
+        &mut self,
+    ) -> impl Iterator<Item = (&Material, &LittleColor, &mut LittleTile)> {
+        self.top_group.iter_tiles_mut()
+    }
+
+    fn parse(root: NbtCompound, errors: Option<&mut Vec<ParseError>>) -> Result<Self, ParseError> {
         let boxes_cnt = get_int_field(&root, "boxes")? as u32;
         let tiles_cnt = get_int_field(&root, "tiles")? as u32;
         let min_arr = get_int_array(&root, "min")?;
         let size_arr = get_int_array(&root, "size")?;
-        if min_arr.len() != 3 || size_arr.len() != 3 {
-            return Err(ParseError::InvalidFormat);
+        for (field, arr) in [("min", &min_arr), ("size", &size_arr)] {
+            if arr.len() != 3 {
+                return Err(ParseError::BadArrayLength {
+                    field: field.to_string(),
+                    expected: 3,
+                    got: arr.len(),
+                });
+            }
         }
         let min_pos = LittlePos {
             x: min_arr[0],
             y: min_arr[1],
             z: min_arr[2],
         };
-        let max_pos = LittlePos {
-            x: min_pos.x + size_arr[0],
-            y: min_pos.y + size_arr[1],
-            z: min_pos.z + size_arr[2],
+        let size = LittlePos {
+            x: size_arr[0],
+            y: size_arr[1],
+            z: size_arr[2],
         };
+        if size.x < 0 || size.y < 0 || size.z < 0 {
+            return Err(ParseError::OutOfRange("size"));
+        }
+        let max_pos = min_pos
+            .checked_add(size)
+            .ok_or(ParseError::Overflow("size"))?;
         // root group shares same shape as any other group
-        let top_group = LittleGroup::try_from(root)?;
+        let top_group = LittleGroup::parse(root, errors)?;
         Ok(LittleBlueprint {
             boxes_cnt,
             tiles_cnt,
@@ -508,6 +1381,48 @@ impl TryInto<NbtCompound> for LittleBlueprint {
     }
 }
 
+/// 蓝图集合：一个 NBT 文件中 `blueprints` 列表下的多个独立蓝图
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlueprintCollection {
+    pub blueprints: Vec<LittleBlueprint>,
+}
+
+impl TryFrom<NbtCompound> for BlueprintCollection {
+    type Error = ParseError;
+
+    fn try_from(root: NbtCompound) -> Result<Self, Self::Error> {
+        let mut map: HashMap<String, NbtTag> = root.into_inner();
+        let list = match map.remove("blueprints") {
+            Some(NbtTag::List(list)) => list,
+            Some(_) => return Err(wrong_type("blueprints", "List")),
+            None => return Err(ParseError::MissingField("blueprints")),
+        };
+        let mut blueprints = Vec::new();
+        for (i, item) in list.into_inner().into_iter().enumerate() {
+            let NbtTag::Compound(entry) = item else {
+                return Err(wrong_type(format!("blueprints[{i}]"), "Compound"));
+            };
+            blueprints.push(LittleBlueprint::try_from(entry)?);
+        }
+        Ok(BlueprintCollection { blueprints })
+    }
+}
+
+impl TryInto<NbtCompound> for BlueprintCollection {
+    type Error = ParseError;
+
+    fn try_into(self) -> Result<NbtCompound, Self::Error> {
+        let mut list = Vec::new();
+        for blueprint in self.blueprints {
+            let entry: NbtCompound = LittleBlueprint::try_into(blueprint)?;
+            list.push(NbtTag::Compound(entry));
+        }
+        let mut root = NbtCompound::new();
+        root.insert("blueprints", NbtTag::List(NbtList::from(list)));
+        Ok(root)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -521,6 +1436,17 @@ mod tests {
         assert_eq!(ar, ar_cur.as_slice());
     }
 
+    #[test]
+    fn test_geom_eq_across_grids() {
+        let coarse = LittleTile::try_from(vec![1, 0, 2, 3, 1, 4]).unwrap();
+        let fine = LittleTile::try_from(vec![2, 0, 4, 6, 2, 8]).unwrap();
+        assert!(coarse.geom_eq(4, &fine, 8));
+        assert!(fine.geom_eq(8, &coarse, 4));
+        assert!(!coarse.geom_eq(4, &fine, 4));
+        // 6 与 4 不成整数倍
+        assert!(!coarse.geom_eq(4, &fine, 6));
+    }
+
     #[test]
     fn test_blueprint() {
         let snbt = r#"
@@ -604,4 +1530,574 @@ mod tests {
             .expect("Failed to convert LittleBlueprint to SNBT");
         assert_eq!(root, root2);
     }
+
+    #[test]
+    fn test_blueprint_collection() {
+        let snbt = r#"
+    {
+        blueprints: [
+            {
+                min: [I; 0, 0, 0],
+                size: [I; 1, 1, 1],
+                boxes: 1,
+                tiles: 1,
+                grid: 4,
+                c: [],
+                t: {
+                    "minecraft:stone": [
+                        [I; -1],
+                        [I; 0, 0, 0, 1, 1, 1]
+                    ]
+                }
+            },
+            {
+                min: [I; 2, 0, 2],
+                size: [I; 2, 1, 1],
+                boxes: 2,
+                tiles: 1,
+                grid: 8,
+                c: [],
+                t: {
+                    "minecraft:oak_planks": [
+                        [I; -16777216],
+                        [I; 2, 0, 2, 3, 1, 3],
+                        [I; 3, 0, 2, 4, 1, 3]
+                    ]
+                }
+            }
+        ]
+    }
+        "#;
+        let root = snbt::parse(snbt).expect("Failed to parse SNBT");
+        let collection = BlueprintCollection::try_from(root.clone())
+            .expect("Failed to convert SNBT to BlueprintCollection");
+        assert_eq!(collection.blueprints.len(), 2);
+        assert_eq!(collection.blueprints[1].top_group.grid, 8);
+        let root2: NbtCompound = BlueprintCollection::try_into(collection)
+            .expect("Failed to convert BlueprintCollection to SNBT");
+        assert_eq!(root, root2);
+    }
+
+    #[test]
+    fn test_iter_tiles_mut() {
+        let mut blueprint = crate::test_data::sample_blueprint();
+        assert_eq!(blueprint.top_group.iter_tiles_mut().count(), 8);
+        for (mat, _, tile) in blueprint.top_group.iter_tiles_mut() {
+            if mat == "minecraft:lime_wool" {
+                *tile = LittleTile::try_from(vec![0, 0, 4, 2, 2, 6]).unwrap();
+            }
+        }
+        let lime = &blueprint.top_group.children[1].children[0].children[0];
+        let tiles = &lime.tiles["minecraft:lime_wool"];
+        let white = LittleColor::try_from(-1).unwrap();
+        assert_eq!(tiles[&white][0].bounds().1, LittlePos { x: 2, y: 2, z: 6 });
+    }
+
+    #[test]
+    fn test_iter_tiles() {
+        let blueprint = crate::test_data::sample_blueprint();
+        assert_eq!(blueprint.iter_tiles().count(), 8);
+        // 本组的小方块排在子组之前
+        let own = blueprint
+            .top_group
+            .tiles
+            .values()
+            .flat_map(|c| c.values())
+            .flatten()
+            .count();
+        let first_nested = blueprint.top_group.children[0].iter_tiles().next().unwrap();
+        let position = blueprint
+            .iter_tiles()
+            .position(|(_, _, tile)| std::ptr::eq(tile, first_nested.2))
+            .unwrap();
+        assert_eq!(position, own);
+        let materials: Vec<_> = blueprint.iter_tiles().map(|(mat, _, _)| mat).collect();
+        assert!(materials.iter().any(|mat| *mat == "minecraft:lime_wool"));
+    }
+
+    #[test]
+    fn test_tiles_ordered_roundtrip() {
+        // 同一颜色标记重复出现，且包含一个后面没有小方块的标记
+        let snbt = r#"{
+            grid: 4,
+            c: [],
+            t: {
+                "minecraft:stone": [
+                    [I; -1],
+                    [I; 0, 0, 0, 1, 1, 1],
+                    [I; -16776961],
+                    [I; 1, 0, 0, 2, 1, 1],
+                    [I; -1],
+                    [I; 2, 0, 0, 3, 1, 1],
+                    [I; 3, 0, 0, 4, 1, 1],
+                    [I; 65535]
+                ]
+            }
+        }"#;
+        let nbt = quartz_nbt::snbt::parse(snbt).unwrap();
+        let group = LittleGroup::try_from(nbt.clone()).unwrap();
+
+        let white = LittleColor::try_from(-1).unwrap();
+        let red = LittleColor::try_from(-16776961).unwrap();
+        let blue = LittleColor::try_from(65535).unwrap();
+        let ordered = group.tiles_ordered();
+        let runs: Vec<(LittleColor, usize)> = ordered["minecraft:stone"]
+            .iter()
+            .map(|(color, tiles)| (*color, tiles.len()))
+            .collect();
+        assert_eq!(runs, vec![(white, 1), (red, 1), (white, 2), (blue, 0)]);
+        assert_eq!(
+            ordered["minecraft:stone"][2].1[1],
+            LittleTile::try_from(vec![3, 0, 0, 4, 1, 1]).unwrap()
+        );
+
+        let to_bytes = |nbt: &NbtCompound| {
+            let mut list = NbtCompound::new();
+            list.insert(
+                "l",
+                nbt.get::<_, &NbtCompound>("t").unwrap().inner()["minecraft:stone"].clone(),
+            );
+            let mut bytes = Vec::new();
+            quartz_nbt::io::write_nbt(
+                &mut bytes,
+                None,
+                &list,
+                quartz_nbt::io::Flavor::Uncompressed,
+            )
+            .unwrap();
+            bytes
+        };
+        let written: NbtCompound = group.clone().try_into().unwrap();
+        assert_eq!(to_bytes(&written), to_bytes(&nbt));
+
+        // 修改后记录失效，退回每种颜色一个标记
+        let mut edited = group;
+        edited
+            .tiles
+            .get_mut("minecraft:stone")
+            .unwrap()
+            .get_mut(&red)
+            .unwrap()
+            .clear();
+        let canonical: Vec<usize> = edited.tiles_ordered()["minecraft:stone"]
+            .iter()
+            .map(|(_, tiles)| tiles.len())
+            .collect();
+        assert_eq!(canonical.iter().sum::<usize>(), 3);
+        assert_eq!(canonical.len(), 2);
+    }
+
+    #[test]
+    fn test_invalid_tile_context() {
+        let snbt =
+            crate::test_data::SAMPLE_SNBT.replace("[I; 2, 0, 6, 3, 1, 7]", "[I; 2, 0, 6, 3, 1]");
+        let nbt = quartz_nbt::snbt::parse(&snbt).unwrap();
+        let err = LittleBlueprint::try_from(nbt).unwrap_err();
+        let ParseError::InvalidTile {
+            material, index, ..
+        } = &err
+        else {
+            panic!("unexpected error {err:?}");
+        };
+        assert_eq!(material, "minecraft:red_wool");
+        assert_eq!(*index, 1);
+        assert!(err.to_string().contains("minecraft:red_wool"));
+    }
+
+    #[test]
+    fn test_structure_variants() {
+        let blueprint = crate::test_data::sample_blueprint();
+        assert_eq!(
+            blueprint.top_group.children[0].structure,
+            Some(LittleStructure::Fixed)
+        );
+        assert_eq!(blueprint.top_group.structure, None);
+
+        // 未知结构连同附加数据原样往返
+        let snbt = crate::test_data::SAMPLE_SNBT.replacen(
+            r#"id: "fixed""#,
+            r#"id: "door", duration: 50, state: { open: 1b }"#,
+            1,
+        );
+        let root = snbt::parse(&snbt).unwrap();
+        let blueprint = LittleBlueprint::try_from(root.clone()).unwrap();
+        let door = blueprint.top_group.children[0].structure.as_ref().unwrap();
+        let LittleStructure::Other { id, data } = door else {
+            panic!("unexpected structure {door:?}");
+        };
+        assert_eq!((id.as_str(), door.id()), ("door", "door"));
+        assert_eq!(data.get::<_, i32>("duration").unwrap(), 50);
+        assert_eq!(LittleBlueprint::roundtrip_check(&root), Ok(()));
+
+        let snbt = crate::test_data::SAMPLE_SNBT.replacen(r#"id: "fixed""#, "id: 1", 1);
+        let err = LittleBlueprint::try_from(snbt::parse(&snbt).unwrap()).unwrap_err();
+        assert!(matches!(err, ParseError::WrongType { .. }));
+    }
+
+    #[test]
+    fn test_specific_errors() {
+        let parse = |from: &str, to: &str| {
+            let snbt = crate::test_data::SAMPLE_SNBT.replace(from, to);
+            LittleBlueprint::try_from(snbt::parse(&snbt).unwrap()).unwrap_err()
+        };
+        let err = parse("boxes: 8,", "");
+        assert!(matches!(err, ParseError::MissingField("boxes")));
+        assert_eq!(err.to_string(), "Missing field `boxes`");
+
+        let err = parse("tiles: 5,", "tiles: 5b,");
+        assert!(matches!(
+            &err,
+            ParseError::WrongType { field, expected: "Int" } if field == "tiles"
+        ));
+        assert_eq!(err.to_string(), "Field `tiles` should be Int");
+
+        let err = parse("size: [I; 5, 1, 5]", "size: [I; 5, 1]");
+        assert!(matches!(
+            &err,
+            ParseError::BadArrayLength { field, expected: 3, got: 2 } if field == "size"
+        ));
+
+        let err = parse(
+            "[I; 2, 0, 6, 3, 1, 7]",
+            "[I; 2, 0, 6, 3, 1, 7, -2147483641]",
+        );
+        let ParseError::InvalidTile { source, .. } = err else {
+            panic!("unexpected error {err:?}");
+        };
+        assert!(matches!(*source, ParseError::InvalidTransformData));
+    }
+
+    #[test]
+    fn test_size_overflow() {
+        let parse = |size: &str| {
+            let snbt = crate::test_data::SAMPLE_SNBT.replace("size: [I; 5, 1, 5]", size);
+            LittleBlueprint::try_from(quartz_nbt::snbt::parse(&snbt).unwrap())
+        };
+        assert!(matches!(
+            parse("size: [I; 5, 1, 2147483645]"),
+            Err(ParseError::Overflow("size"))
+        ));
+        assert!(matches!(
+            parse("size: [I; 5, -1, 5]"),
+            Err(ParseError::OutOfRange("size"))
+        ));
+        let max = parse("size: [I; 5, 1, 2147483644]").unwrap().max_pos;
+        assert_eq!(max.z, i32::MAX);
+    }
+
+    #[test]
+    fn test_grid_tag_roundtrip() {
+        for (grid, expected) in [
+            ("grid: 4s", NbtTag::Short(4)),
+            ("grid: 4", NbtTag::Int(4)),
+            ("grid: 4L", NbtTag::Long(4)),
+        ] {
+            let snbt = format!("{{ {grid}, c: [], t: {{}} }}");
+            let group = LittleGroup::try_from(quartz_nbt::snbt::parse(&snbt).unwrap()).unwrap();
+            assert_eq!(group.grid, 4);
+            let written: NbtCompound = group.try_into().unwrap();
+            assert_eq!(written.inner()["grid"], expected);
+        }
+
+        let snbt = crate::test_data::SAMPLE_SNBT.replace(
+            "grid: 4,\n        t: {\n            \"minecraft:white_wool\"",
+            "grid: 4s,\n        t: {\n            \"minecraft:white_wool\"",
+        );
+        let nbt = quartz_nbt::snbt::parse(&snbt).unwrap();
+        assert_eq!(nbt.inner()["grid"], NbtTag::Short(4));
+        let blueprint = LittleBlueprint::try_from(nbt).unwrap();
+        assert_eq!(blueprint, crate::test_data::sample_blueprint());
+        let written: NbtCompound = blueprint.try_into().unwrap();
+        assert_eq!(written.inner()["grid"], NbtTag::Short(4));
+
+        let negative = quartz_nbt::snbt::parse("{ grid: -1s, c: [], t: {} }").unwrap();
+        assert!(matches!(
+            LittleGroup::try_from(negative),
+            Err(ParseError::OutOfRange("grid"))
+        ));
+    }
+
+    #[test]
+    fn test_try_from_lossy() {
+        let snbt =
+            crate::test_data::SAMPLE_SNBT.replace("[I; 3, 0, 4, 4, 1, 5]", "[I; 3, 0, 4, 4, 1]");
+        let nbt = quartz_nbt::snbt::parse(&snbt).unwrap();
+        assert!(LittleBlueprint::try_from(nbt.clone()).is_err());
+
+        let (blueprint, errors) = LittleBlueprint::try_from_lossy(nbt).unwrap();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            &errors[0],
+            ParseError::InvalidTile { material, index: 2, .. } if material == "minecraft:stone"
+        ));
+        let mut blueprint = blueprint;
+        assert_eq!(blueprint.top_group.iter_tiles_mut().count(), 7);
+
+        let (clean, errors) = LittleBlueprint::try_from_lossy(
+            crate::test_data::sample_blueprint().try_into().unwrap(),
+        )
+        .unwrap();
+        assert!(errors.is_empty());
+        assert_eq!(clean, crate::test_data::sample_blueprint());
+    }
+
+    #[test]
+    fn test_min_size_as_int_list() {
+        let int_array = crate::test_data::sample_blueprint();
+        let snbt = crate::test_data::SAMPLE_SNBT
+            .replace("min: [I; 0, 0, 3]", "min: [0, 0, 3]")
+            .replace("size: [I; 5, 1, 5]", "size: [5, 1, 5]");
+        let int_list = crate::test_data::blueprint_from_snbt(&snbt);
+        assert_eq!(int_array, int_list);
+    }
+
+    #[test]
+    fn test_color_hex() {
+        let color = LittleColor::from_hex("#12345678").unwrap();
+        assert_eq!(LittleColor::try_from(0x1234_5678).unwrap(), color);
+        assert_eq!(color.to_hex(), "#12345678");
+        assert_eq!(LittleColor::from_hex(&color.to_hex()).unwrap(), color);
+
+        let red = LittleColor::from_hex("ff0000").unwrap();
+        assert_eq!(red, LittleColor::try_from(-16776961).unwrap());
+        assert_eq!(red.to_hex(), "#FF0000FF");
+
+        for bad in ["#fff", "#1234567", "#12345g", "", "##123456", "+1234567"] {
+            assert!(matches!(
+                LittleColor::from_hex(bad),
+                Err(ParseError::InvalidHexColor(s)) if s == bad
+            ));
+        }
+    }
+
+    #[test]
+    fn test_color_byte_order() {
+        let color = LittleColor {
+            r: 0x12,
+            g: 0x34,
+            b: 0x56,
+            a: 0x78,
+        };
+        let rgba: i32 = color.try_into().unwrap();
+        assert_eq!(rgba, 0x1234_5678);
+        assert_eq!(LittleColor::try_from(rgba).unwrap(), color);
+
+        assert_eq!(color.to_argb(), 0x7812_3456);
+        assert_eq!(LittleColor::from_argb(0x7812_3456), color);
+
+        // 最高位为 1 时不能因符号扩展而串到其它通道
+        let opaque_red = LittleColor::from_argb(0xFFFF_0000_u32 as i32);
+        assert_eq!(
+            opaque_red,
+            LittleColor {
+                r: 255,
+                g: 0,
+                b: 0,
+                a: 255
+            }
+        );
+        assert_eq!(opaque_red.to_argb(), 0xFFFF_0000_u32 as i32);
+    }
+
+    #[test]
+    fn test_clamp_to_grid() {
+        let mut inverted = LittleTile::try_from(vec![3, 0, 4, 1, 1, 2]).unwrap();
+        assert!(!inverted.is_valid(4));
+        inverted.clamp_to_grid(4);
+        assert!(inverted.is_valid(4));
+        assert_eq!(
+            inverted,
+            LittleTile::try_from(vec![1, 0, 2, 3, 1, 4]).unwrap()
+        );
+
+        let mut far = LittleTile::try_from(vec![0, 0, 0, 1, 1, i32::MAX]).unwrap();
+        assert!(!far.is_valid(4));
+        far.clamp_to_grid(4);
+        assert!(far.is_valid(4));
+        assert_eq!(far.bounds().1.z, 4 * MAX_TILE_BLOCKS);
+    }
+
+    #[test]
+    fn test_display() {
+        let tile = LittleTile::try_from(vec![0, 0, 0, 1, 1, 1]).unwrap();
+        assert_eq!(tile.to_string(), "Box[(0, 0, 0)..(1, 1, 1)]");
+
+        let mut corner: CornerOffsets = enum_map! { _ => enum_map! { _ => 0 } };
+        corner[CORNER_ORDER[0]][Axis::Y] = 2;
+        let tbox = LittleTile::TransformableBox {
+            min_pos: LittlePos { x: -1, y: 0, z: 2 },
+            max_pos: LittlePos { x: 3, y: 4, z: 5 },
+            flips: Flipped::EAST | Flipped::UP,
+            corner,
+        };
+        assert_eq!(
+            tbox.to_string(),
+            "TBox[(-1, 0, 2)..(3, 4, 5), flips: EAST|UP, offsets: 1]"
+        );
+    }
+
+    #[test]
+    fn test_recompute_bounds() {
+        let mut blueprint = crate::test_data::sample_blueprint();
+        let (min_pos, max_pos) = (blueprint.min_pos, blueprint.max_pos);
+        blueprint.min_pos = LittlePos { x: 0, y: 0, z: 0 };
+        blueprint.recompute_bounds();
+        assert_eq!((blueprint.min_pos, blueprint.max_pos), (min_pos, max_pos));
+
+        // 东上北角向上偏移 2，包围盒随之增高
+        let mut corner: CornerOffsets = enum_map! { _ => enum_map! { _ => 0 } };
+        corner[BoxCorner::EUN][Axis::Y] = 2;
+        let tbox = LittleTile::TransformableBox {
+            min_pos: LittlePos { x: 0, y: 0, z: 3 },
+            max_pos: LittlePos { x: 1, y: 1, z: 4 },
+            flips: Flipped::empty(),
+            corner,
+        };
+        assert_eq!(tbox.shape_bounds().1, LittlePos { x: 1, y: 3, z: 4 });
+        let white = LittleColor::try_from(-1).unwrap();
+        blueprint
+            .top_group
+            .tiles
+            .get_mut("minecraft:white_wool")
+            .unwrap()
+            .get_mut(&white)
+            .unwrap()
+            .push(tbox);
+        blueprint.recompute_bounds();
+        assert_eq!(blueprint.max_pos, LittlePos { x: 5, y: 3, z: 8 });
+
+        let empty = LittleGroup::try_from(snbt::parse("{grid: 4, c: [], t: {}}").unwrap()).unwrap();
+        assert_eq!(empty.compute_bounds(), None);
+    }
+
+    #[test]
+    fn test_grid_dimensions() {
+        let snbt = r#"{
+            min: [I; 2, 3, 4],
+            size: [I; 1, 1, 1],
+            boxes: 1,
+            tiles: 1,
+            grid: 1,
+            c: [],
+            t: { "minecraft:stone": [[I; -1], [I; 2, 3, 4, 3, 4, 5]] }
+        }"#;
+        let blueprint = crate::test_data::blueprint_from_snbt(snbt);
+        assert_eq!(
+            blueprint.grid_dimensions(1),
+            (LittlePos { x: 1, y: 2, z: 3 }, [3, 3, 3])
+        );
+        assert_eq!(
+            blueprint.grid_dimensions(0),
+            (LittlePos { x: 2, y: 3, z: 4 }, [1, 1, 1])
+        );
+    }
+
+    #[test]
+    fn test_mirror_transformable() {
+        let mut corner: CornerOffsets = enum_map! { _ => enum_map! { _ => 0 } };
+        corner[BoxCorner::EUN][Axis::Y] = 2;
+        corner[BoxCorner::EUN][Axis::X] = 1;
+        let tbox = LittleTile::TransformableBox {
+            min_pos: LittlePos { x: 0, y: 0, z: 0 },
+            max_pos: LittlePos { x: 2, y: 2, z: 2 },
+            flips: Flipped::EAST | Flipped::UP,
+            corner,
+        };
+        let mirrored = tbox.mirrored(Axis::X, 0).unwrap();
+        let LittleTile::TransformableBox { flips, corner, .. } = &mirrored else {
+            unreachable!();
+        };
+        assert_eq!(*flips, Flipped::WEST | Flipped::UP);
+        assert_eq!(corner[BoxCorner::WUN][Axis::X], -1);
+        assert_eq!(corner[BoxCorner::WUN][Axis::Y], 2);
+        assert_eq!(corner[BoxCorner::EUN][Axis::Y], 0);
+        // 实际形状与原形状关于镜面对称
+        let (min_pos, max_pos) = tbox.shape_bounds();
+        let (min2, max2) = mirrored.shape_bounds();
+        assert_eq!((min2.x, max2.x), (-max_pos.x, -min_pos.x));
+        assert_eq!((min2.y, max2.y), (min_pos.y, max_pos.y));
+        assert_eq!(mirrored.mirrored(Axis::X, 0).unwrap(), tbox);
+    }
+
+    #[test]
+    fn test_count_tiles() {
+        let mut blueprint = crate::test_data::sample_blueprint();
+        assert_eq!(blueprint.count_tiles(), (8, 5));
+
+        // 同材质新颜色多一个 tile，同颜色追加盒子只增加 box
+        let red = LittleColor::try_from(-16776961).unwrap();
+        let white = LittleColor::try_from(-1).unwrap();
+        let wool = blueprint
+            .top_group
+            .tiles
+            .get_mut("minecraft:white_wool")
+            .unwrap();
+        let tile = LittleTile::try_from(vec![0, 0, 0, 1, 1, 1]).unwrap();
+        wool.insert(red, vec![tile.clone()]);
+        wool.get_mut(&white).unwrap().push(tile);
+        blueprint.recompute_counts();
+        assert_eq!((blueprint.boxes_cnt, blueprint.tiles_cnt), (10, 6));
+    }
+
+    #[test]
+    fn test_normalize_boxes() {
+        let snbt =
+            crate::test_data::SAMPLE_SNBT.replace("[I; 2, 0, 6, 3, 1, 7]", "[I; 3, 0, 6, 1, 1, 7]");
+        let mut group = crate::test_data::blueprint_from_snbt(&snbt).top_group;
+        let white = LittleColor::try_from(-1).unwrap();
+        let inverted = &group.children[1].tiles["minecraft:red_wool"][&white][0];
+        assert!(!inverted.is_normalized());
+        assert_eq!(inverted.volume(), 0);
+        assert_eq!(inverted.cells().count(), 0);
+
+        assert_eq!(group.normalize_boxes(), 1);
+        assert_eq!(group.normalize_boxes(), 0);
+        let fixed = &group.children[1].tiles["minecraft:red_wool"][&white][0];
+        assert_eq!(fixed.volume(), 2);
+        let cells: Vec<LittlePos> = fixed.cells().collect();
+        assert_eq!(
+            cells,
+            vec![
+                LittlePos { x: 1, y: 0, z: 6 },
+                LittlePos { x: 2, y: 0, z: 6 }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_transformable_data_bijective() {
+        // 每组为若干 (角, 轴, 偏移量)
+        let patterns: Vec<Vec<(usize, usize, i16)>> = vec![
+            vec![],
+            vec![(0, 0, 1)],
+            vec![(7, 2, -1)],
+            vec![(0, 1, 3), (1, 0, -2)],
+            vec![(2, 2, 5), (3, 0, 7), (6, 1, -4)],
+            (0..24)
+                .map(|i| (i / 3, i % 3, i as i16 - 12))
+                .filter(|p| p.2 != 0)
+                .collect(),
+        ];
+        for bits in 0..=0x3F_u8 {
+            let flips = Flipped::from_bits_truncate(bits);
+            for pattern in &patterns {
+                let mut corner_offsets: CornerOffsets = enum_map! { _ => enum_map! { _ => 0 } };
+                for &(corner_i, ax_i, offset) in pattern {
+                    corner_offsets[CORNER_ORDER[corner_i]][Axis::ALL[ax_i]] = offset;
+                }
+                let words = encode_transformable_data(flips, &corner_offsets).unwrap();
+                // 魔数位、翻转位与偏移标志位互不重叠
+                let head = words[0] as u32;
+                assert_eq!(head >> 31, 1);
+                assert_eq!((head >> 24) & 0x3F, u32::from(bits));
+                assert_eq!((head >> 30) & 1, 0);
+
+                let (flips2, corner_offsets2) = decode_transformable_data(&words).unwrap();
+                assert_eq!(flips2, flips);
+                assert_eq!(corner_offsets2, corner_offsets, "pattern {pattern:?}");
+                let words2 = encode_transformable_data(flips2, &corner_offsets2).unwrap();
+                assert_eq!(words2, words);
+            }
+        }
+    }
 }
@@ -0,0 +1,334 @@
+use crate::{Axis, LittleGroup, LittlePos, LittleTile, ParseError};
+
+/// 掩码的三维尺寸与索引：`x + dx * (y + dy * z)`，与 `for_each_chunk` 一致
+struct Mask<'a> {
+    dims: [usize; 3],
+    filled: &'a [bool],
+    visited: Vec<bool>,
+}
+
+impl Mask<'_> {
+    fn index(&self, x: usize, y: usize, z: usize) -> usize {
+        x + self.dims[0] * (y + self.dims[1] * z)
+    }
+
+    /// 单元格已填充且尚未被任何盒子覆盖
+    fn free(&self, x: usize, y: usize, z: usize) -> bool {
+        let i = self.index(x, y, z);
+        self.filled[i] && !self.visited[i]
+    }
+
+    /// `[x0, x1) × [y0, y1)` 在第 z 层全部空闲
+    fn rect_free(&self, (x0, x1): (usize, usize), (y0, y1): (usize, usize), z: usize) -> bool {
+        (y0..y1).all(|y| (x0..x1).all(|x| self.free(x, y, z)))
+    }
+
+    /// 标记盒子已覆盖并生成对应的小方块
+    fn take(&mut self, min: [usize; 3], max: [usize; 3]) -> LittleTile {
+        for z in min[2]..max[2] {
+            for y in min[1]..max[1] {
+                for x in min[0]..max[0] {
+                    let i = self.index(x, y, z);
+                    self.visited[i] = true;
+                }
+            }
+        }
+        let pos = |p: [usize; 3]| LittlePos {
+            x: p[0] as i32,
+            y: p[1] as i32,
+            z: p[2] as i32,
+        };
+        LittleTile::Box {
+            min_pos: pos(min),
+            max_pos: pos(max),
+        }
+    }
+
+    /// 从第 z 层的矩形出发沿 Z 轴尽量延伸，返回终止层（不含）
+    fn extend_z(&self, xs: (usize, usize), ys: (usize, usize), z: usize) -> usize {
+        let mut z1 = z + 1;
+        while z1 < self.dims[2] && self.rect_free(xs, ys, z1) {
+            z1 += 1;
+        }
+        z1
+    }
+}
+
+fn checked_mask<'a>(grid: u16, dims: [u32; 3], mask: &'a [bool]) -> Result<Mask<'a>, ParseError> {
+    let bounds = LittleTile::Box {
+        min_pos: LittlePos { x: 0, y: 0, z: 0 },
+        max_pos: LittlePos {
+            x: i32::try_from(dims[0]).map_err(|_| ParseError::Overflow("dims"))?,
+            y: i32::try_from(dims[1]).map_err(|_| ParseError::Overflow("dims"))?,
+            z: i32::try_from(dims[2]).map_err(|_| ParseError::Overflow("dims"))?,
+        },
+    };
+    if !bounds.is_valid(grid) {
+        return Err(ParseError::Overflow("dims"));
+    }
+    let dims = dims.map(|d| d as usize);
+    let expected = dims.iter().product::<usize>();
+    if mask.len() != expected {
+        return Err(ParseError::BadArrayLength {
+            field: "mask".to_string(),
+            expected,
+            got: mask.len(),
+        });
+    }
+    Ok(Mask {
+        dims,
+        filled: mask,
+        visited: vec![false; mask.len()],
+    })
+}
+
+/// 朴素贪心合并：按索引顺序找到第一个空闲单元格，依次沿 X、Y、Z 尽量延伸
+///
+/// `mask` 按 `x + dx * (y + dy * z)` 排列，输出盒子以单元格为单位，需能在 `grid` 精度下表示
+pub fn greedy_mesh(
+    grid: u16,
+    dims: [u32; 3],
+    mask: &[bool],
+) -> Result<Vec<LittleTile>, ParseError> {
+    let mut m = checked_mask(grid, dims, mask)?;
+    let [dx, dy, dz] = m.dims;
+    let mut tiles = Vec::new();
+    for z in 0..dz {
+        for y in 0..dy {
+            for x in 0..dx {
+                if !m.free(x, y, z) {
+                    continue;
+                }
+                let mut x1 = x + 1;
+                while x1 < dx && m.free(x1, y, z) {
+                    x1 += 1;
+                }
+                let mut y1 = y + 1;
+                while y1 < dy && m.rect_free((x, x1), (y1, y1 + 1), z) {
+                    y1 += 1;
+                }
+                let z1 = m.extend_z((x, x1), (y, y1), z);
+                tiles.push(m.take([x, y, z], [x1, y1, z1]));
+            }
+        }
+    }
+    Ok(tiles)
+}
+
+/// 层内矩形 `((x0, x1), (y0, y1))`，均为左闭右开
+type Rect = ((usize, usize), (usize, usize));
+
+/// 在第 z 层的空闲单元格中找面积最大的矩形，面积相同时取先找到的
+fn largest_rect(m: &Mask, z: usize) -> Option<Rect> {
+    let [dx, dy, _] = m.dims;
+    let mut heights = vec![0usize; dx];
+    let mut best: Option<(usize, Rect)> = None;
+    for y in 0..dy {
+        for (x, h) in heights.iter_mut().enumerate() {
+            *h = if m.free(x, y, z) { *h + 1 } else { 0 };
+        }
+        // 直方图最大矩形：单调栈保存高度递增的列
+        let mut stack: Vec<usize> = Vec::new();
+        for x in 0..=dx {
+            let h = if x < dx { heights[x] } else { 0 };
+            while let Some(&top) = stack.last() {
+                if heights[top] <= h {
+                    break;
+                }
+                stack.pop();
+                let left = stack.last().map_or(0, |&l| l + 1);
+                let area = heights[top] * (x - left);
+                if best.is_none_or(|(a, _)| area > a) {
+                    best = Some((area, ((left, x), (y + 1 - heights[top], y + 1))));
+                }
+            }
+            stack.push(x);
+        }
+    }
+    best.map(|(_, rect)| rect)
+}
+
+/// 近似最少盒子的合并：逐层反复取面积最大的矩形，再沿 Z 轴延伸
+///
+/// 参数与 [`greedy_mesh`] 相同，通常产生更少的盒子（即更小的 `boxes_cnt`）
+pub fn greedy_mesh_optimal(
+    grid: u16,
+    dims: [u32; 3],
+    mask: &[bool],
+) -> Result<Vec<LittleTile>, ParseError> {
+    let mut m = checked_mask(grid, dims, mask)?;
+    let mut tiles = Vec::new();
+    for z in 0..m.dims[2] {
+        while let Some((xs, ys)) = largest_rect(&m, z) {
+            let z1 = m.extend_z(xs, ys, z);
+            tiles.push(m.take([xs.0, ys.0, z], [xs.1, ys.1, z1]));
+        }
+    }
+    Ok(tiles)
+}
+
+impl LittleGroup {
+    /// 在本组及所有后代组中，把同材质同颜色、共享一整个面的相邻普通盒子合并为更大的盒子，
+    /// 依次沿 X、Y、Z 扫描直到无法继续合并，返回减少的盒子数量。
+    /// 变换盒不参与合并，保持原样排在合并结果之后
+    pub fn merge_boxes(&mut self) -> usize {
+        let mut merged = 0;
+        for tiles in self.tiles.values_mut().flat_map(|c| c.values_mut()) {
+            let before = tiles.len();
+            let (mut boxes, rest): (Vec<_>, Vec<_>) = std::mem::take(tiles)
+                .into_iter()
+                .partition(|tile| matches!(tile, LittleTile::Box { .. }));
+            let mut bounds: Vec<_> = boxes.iter().map(LittleTile::bounds).collect();
+            let mut changed = true;
+            while changed {
+                changed = false;
+                for axis in Axis::ALL {
+                    changed |= merge_along(&mut bounds, axis);
+                }
+            }
+            if bounds.len() < boxes.len() {
+                boxes = bounds
+                    .into_iter()
+                    .map(|(min_pos, max_pos)| LittleTile::Box { min_pos, max_pos })
+                    .collect();
+            }
+            *tiles = boxes;
+            tiles.extend(rest);
+            merged += before - tiles.len();
+        }
+        for child in &mut self.children {
+            merged += child.merge_boxes();
+        }
+        merged
+    }
+}
+
+/// 沿 `axis` 合并首尾相接、另外两轴范围完全相同的盒子，返回是否有合并
+fn merge_along(bounds: &mut Vec<(LittlePos, LittlePos)>, axis: Axis) -> bool {
+    let others: Vec<Axis> = Axis::ALL.into_iter().filter(|&a| a != axis).collect();
+    let cross = |(min_pos, max_pos): &(LittlePos, LittlePos)| {
+        others
+            .iter()
+            .map(|&a| (min_pos.get(a), max_pos.get(a)))
+            .collect::<Vec<_>>()
+    };
+    bounds.sort_by_key(|b| (cross(b), b.0.get(axis)));
+    let before = bounds.len();
+    let mut result: Vec<(LittlePos, LittlePos)> = Vec::with_capacity(before);
+    for b in bounds.drain(..) {
+        match result.last_mut() {
+            Some(last) if cross(last) == cross(&b) && last.1.get(axis) == b.0.get(axis) => {
+                *last.1.get_mut(axis) = b.1.get(axis);
+            }
+            _ => result.push(b),
+        }
+    }
+    *bounds = result;
+    bounds.len() < before
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mask_from_rows(rows: &[&str]) -> ([u32; 3], Vec<bool>) {
+        // rows[0] 为 y = 0，单层 z = 0
+        let dims = [rows[0].len() as u32, rows.len() as u32, 1];
+        let mask = rows
+            .iter()
+            .flat_map(|row| row.chars().map(|c| c == '#'))
+            .collect();
+        (dims, mask)
+    }
+
+    fn covered(dims: [u32; 3], tiles: &[LittleTile]) -> Vec<bool> {
+        let mut cells = vec![false; dims.iter().product::<u32>() as usize];
+        for tile in tiles {
+            let (min, max) = tile.bounds();
+            for z in min.z..max.z {
+                for y in min.y..max.y {
+                    for x in min.x..max.x {
+                        let i = (x + dims[0] as i32 * (y + dims[1] as i32 * z)) as usize;
+                        assert!(!cells[i], "overlapping boxes");
+                        cells[i] = true;
+                    }
+                }
+            }
+        }
+        cells
+    }
+
+    #[test]
+    fn test_greedy_mesh_l_shape() {
+        // L 形区域沿 Z 拉伸两层
+        let (mut dims, layer) = mask_from_rows(&["####", "#...", "#..."]);
+        dims[2] = 2;
+        let mask = [layer.clone(), layer].concat();
+        let greedy = greedy_mesh(4, dims, &mask).unwrap();
+        let optimal = greedy_mesh_optimal(4, dims, &mask).unwrap();
+        assert_eq!(covered(dims, &greedy), mask);
+        assert_eq!(covered(dims, &optimal), mask);
+        assert_eq!(optimal.len(), 2);
+        assert!(optimal.len() <= greedy.len());
+
+        // 贪心先吃掉下方的 2x2，横条被切成三段
+        let (dims, mask) = mask_from_rows(&["..##..", "######"]);
+        let greedy = greedy_mesh(4, dims, &mask).unwrap();
+        let optimal = greedy_mesh_optimal(4, dims, &mask).unwrap();
+        assert_eq!(covered(dims, &optimal), mask);
+        assert_eq!(greedy.len(), 3);
+        assert_eq!(optimal.len(), 2);
+    }
+
+    #[test]
+    fn test_greedy_mesh_rejects_bad_input() {
+        assert!(matches!(
+            greedy_mesh(4, [2, 2, 2], &[true; 7]),
+            Err(ParseError::BadArrayLength { field, expected: 8, got: 7 }) if field == "mask"
+        ));
+        assert!(matches!(
+            greedy_mesh_optimal(1, [2000, 1, 1], &[true; 2000]),
+            Err(ParseError::Overflow("dims"))
+        ));
+    }
+
+    #[test]
+    fn test_merge_boxes() {
+        // 2x2x1 的单元格方块加一个变换盒
+        let mut group = crate::test_data::sample_blueprint().top_group.children[0].clone();
+        let white = crate::LittleColor::try_from(-1).unwrap();
+        let stone = group.tiles.get_mut("minecraft:stone").unwrap();
+        let tbox = LittleTile::try_from(vec![0, 0, 0, 1, 1, 1, -2147483648]).unwrap();
+        stone.get_mut(&white).unwrap().push(tbox.clone());
+        assert_eq!(group.merge_boxes(), 3);
+        let tiles = &group.tiles["minecraft:stone"][&white];
+        assert_eq!(tiles.len(), 2);
+        assert_eq!(
+            tiles[0].bounds(),
+            (
+                LittlePos { x: 3, y: 0, z: 3 },
+                LittlePos { x: 5, y: 1, z: 5 }
+            )
+        );
+        assert_eq!(tiles[1], tbox);
+        assert_eq!(group.merge_boxes(), 0);
+
+        // 只有部分面相接的盒子不合并
+        let (dims, mask) = mask_from_rows(&["##.", "###"]);
+        let tiles: Vec<_> = (0..6)
+            .filter(|&i| mask[i])
+            .map(|i| {
+                let (x, y) = (i as i32 % 3, i as i32 / 3);
+                LittleTile::try_from(vec![x, y, 0, x + 1, y + 1, 1]).unwrap()
+            })
+            .collect();
+        let mut group = LittleGroup {
+            tiles: [("minecraft:stone".to_string(), [(white, tiles)].into())].into(),
+            ..group
+        };
+        assert_eq!(group.merge_boxes(), 3);
+        let merged = &group.tiles["minecraft:stone"][&white];
+        assert_eq!(covered(dims, merged), mask);
+        assert_eq!(merged.len(), 2);
+    }
+}
@@ -1,2 +1,28 @@
+mod builder;
+mod chunk;
+mod diff;
+mod edit;
+mod fingerprint;
+mod instance;
+mod limits;
 mod little_tiles;
+mod mesh;
+mod nbt_io;
+mod occupancy;
+mod palette;
+mod roundtrip;
+mod stats;
+#[cfg(test)]
+mod test_data;
+mod transform;
+mod vox;
+pub use builder::*;
+pub use diff::*;
+pub use edit::*;
+pub use fingerprint::*;
+pub use instance::*;
+pub use limits::*;
 pub use little_tiles::*;
+pub use mesh::*;
+pub use occupancy::*;
+pub use stats::*;
@@ -0,0 +1,99 @@
+use crate::{LittleBlueprint, ParseError};
+use quartz_nbt::NbtCompound;
+use quartz_nbt::io::{Flavor, NbtIoError, read_nbt, write_nbt};
+use std::path::Path;
+
+/// gzip 流的魔数
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+impl From<NbtIoError> for ParseError {
+    fn from(err: NbtIoError) -> Self {
+        ParseError::Io(err)
+    }
+}
+
+impl From<std::io::Error> for ParseError {
+    fn from(err: std::io::Error) -> Self {
+        ParseError::Io(err.into())
+    }
+}
+
+impl LittleBlueprint {
+    /// 读取游戏导出的二进制 NBT 文件，按文件头自动识别 gzip 压缩与未压缩两种格式
+    pub fn from_nbt_file(path: impl AsRef<Path>) -> Result<Self, ParseError> {
+        let bytes = std::fs::read(path)?;
+        if bytes.starts_with(&GZIP_MAGIC) {
+            let (root, _) = read_nbt(&mut bytes.as_slice(), Flavor::GzCompressed)?;
+            LittleBlueprint::try_from(root)
+        } else {
+            LittleBlueprint::from_nbt_bytes(&bytes)
+        }
+    }
+
+    /// 写出 gzip 压缩的二进制 NBT 文件，与游戏导出的格式一致
+    pub fn to_nbt_file(&self, path: impl AsRef<Path>) -> Result<(), ParseError> {
+        let root: NbtCompound = self.clone().try_into()?;
+        let mut file = std::fs::File::create(path)?;
+        write_nbt(&mut file, Some(""), &root, Flavor::GzCompressed)?;
+        Ok(())
+    }
+
+    /// 从未压缩的大端二进制 NBT 解析
+    pub fn from_nbt_bytes(mut bytes: &[u8]) -> Result<Self, ParseError> {
+        let (root, _) = read_nbt(&mut bytes, Flavor::Uncompressed)?;
+        LittleBlueprint::try_from(root)
+    }
+
+    /// 序列化为未压缩的大端二进制 NBT，根标签名为空
+    pub fn to_nbt_bytes(&self) -> Result<Vec<u8>, ParseError> {
+        let root: NbtCompound = self.clone().try_into()?;
+        let mut bytes = Vec::new();
+        write_nbt(&mut bytes, Some(""), &root, Flavor::Uncompressed)?;
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::{SAMPLE_SNBT, sample_blueprint};
+    use quartz_nbt::snbt;
+
+    #[test]
+    fn test_nbt_bytes_roundtrip() {
+        let root = snbt::parse(SAMPLE_SNBT).unwrap();
+        let blueprint = LittleBlueprint::try_from(root.clone()).unwrap();
+        let bytes = blueprint.to_nbt_bytes().unwrap();
+        let parsed = LittleBlueprint::from_nbt_bytes(&bytes).unwrap();
+        assert_eq!(parsed, blueprint);
+        let root2: NbtCompound = parsed.try_into().unwrap();
+        assert_eq!(root2, root);
+
+        assert!(matches!(
+            LittleBlueprint::from_nbt_bytes(&bytes[..bytes.len() / 2]),
+            Err(ParseError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_nbt_file_roundtrip() {
+        let dir = std::env::temp_dir();
+        let gzipped = dir.join(format!("voxel-cad-{}-gz.nbt", std::process::id()));
+        let plain = dir.join(format!("voxel-cad-{}-plain.nbt", std::process::id()));
+
+        let blueprint = sample_blueprint();
+        blueprint.to_nbt_file(&gzipped).unwrap();
+        assert!(std::fs::read(&gzipped).unwrap().starts_with(&GZIP_MAGIC));
+        assert_eq!(LittleBlueprint::from_nbt_file(&gzipped).unwrap(), blueprint);
+
+        std::fs::write(&plain, blueprint.to_nbt_bytes().unwrap()).unwrap();
+        assert_eq!(LittleBlueprint::from_nbt_file(&plain).unwrap(), blueprint);
+
+        std::fs::remove_file(&gzipped).unwrap();
+        std::fs::remove_file(&plain).unwrap();
+        assert!(matches!(
+            LittleBlueprint::from_nbt_file(&plain),
+            Err(ParseError::Io(_))
+        ));
+    }
+}
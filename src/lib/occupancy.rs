@@ -0,0 +1,1186 @@
+use crate::{
+    Axis, Facing, LittleBlueprint, LittleColor, LittleGroup, LittlePos, LittleTile, Material,
+    ParseError,
+};
+use std::collections::{HashMap, HashSet};
+
+/// [`LittleBlueprint::iter_tiles_absolute`] 的输出项：`(材质, 颜色, min, max)`
+type AbsoluteTile<'a> = (&'a Material, &'a LittleColor, [f64; 3], [f64; 3]);
+
+/// [`LittleBlueprint::iter_tiles_morton`] 的输出项：`(材质, 颜色, 小方块, 顶层精度下的最小角点)`
+type MortonTile<'a> = (&'a Material, &'a LittleColor, &'a LittleTile, LittlePos);
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// 两个精度的最小公倍数，在 u64 中计算，结果超出 `u16` 时返回 `Overflow("grid")`
+pub(crate) fn checked_lcm(a: u16, b: u16) -> Result<u16, ParseError> {
+    let divisor = u64::from(gcd(u32::from(a), u32::from(b)).max(1));
+    (u64::from(a) / divisor)
+        .checked_mul(u64::from(b))
+        .and_then(|grid| u16::try_from(grid).ok())
+        .ok_or(ParseError::Overflow("grid"))
+}
+
+/// 将三维坐标沿 `axis` 投影为 `(u, v)` 平面坐标：X -> (y, z)，Y -> (x, z)，Z -> (x, y)
+pub(crate) fn project(pos: LittlePos, axis: Axis) -> (i32, i32) {
+    match axis {
+        Axis::X => (pos.y, pos.z),
+        Axis::Y => (pos.x, pos.z),
+        Axis::Z => (pos.x, pos.y),
+    }
+}
+
+/// 三个分量各取低 21 位交错成 64 位 Morton 码，X 占最低位
+fn morton_code(pos: [u32; 3]) -> u64 {
+    let mut code = 0;
+    for bit in 0..21 {
+        for (axis, value) in pos.iter().enumerate() {
+            code |= u64::from((value >> bit) & 1) << (bit * 3 + axis);
+        }
+    }
+    code
+}
+
+/// 坐标原点约定
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum CoordConvention {
+    /// LittleTiles 原生约定：原点为蓝图空间原点，`max = min + size`
+    #[default]
+    MinCorner,
+    /// 以蓝图包围盒（`min_pos..max_pos`）的中心为原点，尺寸为奇数时会出现半格坐标
+    Center,
+}
+
+/// 统计占用时如何对待全透明（`a == 0`）的单元格
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum OccupancyMode {
+    /// 按几何形状统计，透明单元格同样算作占据（适用于碰撞）
+    #[default]
+    Geometric,
+    /// 只统计可见的单元格：全透明的小方块在光栅化前剔除，不会遮挡与之重叠的不透明小方块
+    Visible,
+}
+
+/// 公共精度下的体素占用表：每个被占据的单元格记录其材质与颜色。
+/// 多个小方块重叠时，按组的前序、组内按材质名与颜色排序，靠后的覆盖靠前的，
+/// 因此结果与哈希表遍历顺序无关。
+/// 由 [`LittleBlueprint::occupancy_map`] 构建一次，传给各项统计的 `map` 参数即可复用；
+/// 占用表须来自同一蓝图，模式与统计所需不符时统计会重新光栅化
+pub struct OccupancyMap<'a> {
+    pub cells: HashMap<LittlePos, (&'a Material, LittleColor)>,
+    /// 单元格所处的精度
+    grid: u32,
+    /// 顶层组的精度，用于把结果换算回顶层精度
+    top_grid: u16,
+    mode: OccupancyMode,
+}
+
+impl OccupancyMap<'_> {
+    /// 被占据单元格的包围盒 `(min, max)`，`max` 不包含在内；为空时返回 None
+    pub fn bounds(&self) -> Option<(LittlePos, LittlePos)> {
+        self.cells.keys().fold(None, |acc, &pos| {
+            let end = LittlePos {
+                x: pos.x + 1,
+                y: pos.y + 1,
+                z: pos.z + 1,
+            };
+            match acc {
+                None => Some((pos, end)),
+                Some((lo, hi)) => Some((lo.component_min(pos), hi.component_max(end))),
+            }
+        })
+    }
+
+    /// 见 [`LittleBlueprint::enclosed_materials`]
+    pub(crate) fn enclosed_materials(&self) -> HashSet<Material> {
+        let mut visible: HashSet<&Material> = HashSet::new();
+        for (&pos, &(mat, _)) in &self.cells {
+            if Facing::ALL
+                .into_iter()
+                .any(|facing| !self.cells.contains_key(&pos.offset(facing)))
+            {
+                visible.insert(mat);
+            }
+        }
+        self.cells
+            .values()
+            .map(|&(mat, _)| mat)
+            .filter(|mat| !visible.contains(mat))
+            .cloned()
+            .collect()
+    }
+
+    /// 见 [`LittleBlueprint::material_interfaces`]，结果按坐标排序
+    pub(crate) fn material_interfaces(&self) -> Vec<(LittlePos, Facing, Material, Material)> {
+        let mut faces = Vec::new();
+        for (&pos, &(mat, _)) in &self.cells {
+            for facing in [Facing::East, Facing::Up, Facing::South] {
+                match self.cells.get(&pos.offset(facing)) {
+                    Some(&(other, _)) if other != mat => {
+                        faces.push((pos, facing, mat.clone(), other.clone()));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        faces.sort_by_key(|&(pos, facing, _, _)| (pos, facing as u8));
+        faces
+    }
+
+    /// 被占据的单元格坐标，按坐标排序
+    pub(crate) fn occupied_coords(&self) -> Vec<LittlePos> {
+        let mut coords: Vec<LittlePos> = self.cells.keys().copied().collect();
+        coords.sort();
+        coords
+    }
+
+    /// 被占据的单元格数
+    pub(crate) fn volume(&self) -> u64 {
+        self.cells.len() as u64
+    }
+
+    /// 见 [`LittleBlueprint::bounding_sphere`]，结果换算回顶层精度
+    pub(crate) fn bounding_sphere(&self) -> Option<([f32; 3], f32)> {
+        let (min, max) = self.bounds()?;
+        let scale = f32::from(self.top_grid) / self.grid as f32;
+        let center = [
+            (min.x + max.x) as f32 / 2.0 * scale,
+            (min.y + max.y) as f32 / 2.0 * scale,
+            (min.z + max.z) as f32 / 2.0 * scale,
+        ];
+        let half = [
+            (max.x - min.x) as f32 / 2.0 * scale,
+            (max.y - min.y) as f32 / 2.0 * scale,
+            (max.z - min.z) as f32 / 2.0 * scale,
+        ];
+        let radius = half.iter().map(|h| h * h).sum::<f32>().sqrt();
+        Some((center, radius))
+    }
+
+    /// 见 [`LittleBlueprint::render_orthographic`]
+    pub(crate) fn render_orthographic(&self, axis: Axis, size: u32) -> Vec<LittleColor> {
+        let mut pixels = vec![LittleColor::default(); (size as usize).pow(2)];
+        let Some((min, max)) = self.bounds() else {
+            return pixels;
+        };
+        let mut nearest: HashMap<(i32, i32), (i32, LittleColor)> = HashMap::new();
+        for (&pos, &(_, color)) in &self.cells {
+            let depth = pos.get(axis);
+            let entry = nearest.entry(project(pos, axis)).or_insert((depth, color));
+            if depth < entry.0 {
+                *entry = (depth, color);
+            }
+        }
+
+        let (min_u, min_v) = project(min, axis);
+        let (max_u, max_v) = project(max, axis);
+        let span = i64::from((max_u - min_u).max(max_v - min_v));
+        for py in 0..size {
+            for px in 0..size {
+                let u = min_u + (i64::from(px) * span / i64::from(size)) as i32;
+                let v = min_v + (i64::from(py) * span / i64::from(size)) as i32;
+                if let Some(&(_, color)) = nearest.get(&(u, v)) {
+                    pixels[(py * size + px) as usize] = color;
+                }
+            }
+        }
+        pixels
+    }
+
+    /// 见 [`LittleBlueprint::silhouette_areas`]
+    pub(crate) fn silhouette_areas(&self) -> [u64; 3] {
+        Axis::ALL.map(|axis| {
+            let projected: HashSet<(i32, i32)> =
+                self.cells.keys().map(|&pos| project(pos, axis)).collect();
+            projected.len() as u64
+        })
+    }
+
+    /// 见 [`LittleBlueprint::slice`]
+    pub(crate) fn slice(&self, axis: Axis, layer: i32) -> Vec<(i32, i32, LittleColor)> {
+        let mut cells: Vec<_> = self
+            .cells
+            .iter()
+            .filter(|(pos, _)| pos.get(axis) == layer)
+            .map(|(&pos, &(_, color))| {
+                let (u, v) = project(pos, axis);
+                (u, v, color)
+            })
+            .collect();
+        cells.sort_by_key(|&(u, v, _)| (u, v));
+        cells
+    }
+}
+
+impl LittleGroup {
+    /// 前序遍历自身与所有后代组
+    pub(crate) fn for_each_group<'a>(&'a self, f: &mut impl FnMut(&'a LittleGroup)) {
+        f(self);
+        for child in &self.children {
+            child.for_each_group(f);
+        }
+    }
+}
+
+impl LittleBlueprint {
+    /// 按公共精度遍历所有小方块的基础包围盒，坐标按 `convention` 换算，
+    /// 输出 `(材质, 颜色, min, max)`；公共精度超出 `u16` 时返回错误
+    pub fn iter_tiles_absolute(
+        &self,
+        convention: CoordConvention,
+    ) -> Result<impl Iterator<Item = AbsoluteTile<'_>>, ParseError> {
+        let grid = u32::from(self.lcm_grid()?);
+        let origin = match convention {
+            CoordConvention::MinCorner => [0.0; 3],
+            CoordConvention::Center => {
+                // min_pos/max_pos 处于顶层精度
+                let top = f64::from(grid) / f64::from(self.top_group.grid.max(1));
+                let (min, max) = (self.min_pos, self.max_pos);
+                [
+                    f64::from(min.x + max.x) * top / 2.0,
+                    f64::from(min.y + max.y) * top / 2.0,
+                    f64::from(min.z + max.z) * top / 2.0,
+                ]
+            }
+        };
+        let mut groups = Vec::new();
+        self.top_group.for_each_group(&mut |group| {
+            if group.grid != 0 {
+                groups.push(group);
+            }
+        });
+        Ok(groups.into_iter().flat_map(move |group| {
+            let factor = f64::from(grid / u32::from(group.grid));
+            let convert = move |pos: LittlePos| {
+                [
+                    f64::from(pos.x) * factor - origin[0],
+                    f64::from(pos.y) * factor - origin[1],
+                    f64::from(pos.z) * factor - origin[2],
+                ]
+            };
+            group.tiles.iter().flat_map(move |(mat, color_tiles)| {
+                color_tiles.iter().flat_map(move |(color, tiles)| {
+                    tiles.iter().map(move |tile| {
+                        let (min_pos, max_pos) = tile.bounds();
+                        (mat, color, convert(min_pos), convert(max_pos))
+                    })
+                })
+            })
+        }))
+    }
+
+    /// 按最小角点的 Morton 码（Z 序）遍历所有小方块，输出 `(材质, 颜色, 小方块, 顶层精度下的最小角点)`。
+    /// 子组坐标换算到顶层精度时向下取整；码相同时依次按组的前序下标、材质、颜色、包围盒排序，
+    /// 同一列表内保持原顺序，因此结果与哈希表遍历顺序无关。
+    /// 换算后的角点超出 i32 时返回 `Overflow("tile")`
+    pub fn iter_tiles_morton(&self) -> Result<impl Iterator<Item = MortonTile<'_>>, ParseError> {
+        let top = i64::from(self.top_group.grid.max(1));
+        let mut items = Vec::new();
+        let mut group_index = 0;
+        let mut result = Ok(());
+        self.top_group.for_each_group(&mut |group| {
+            if group.grid != 0 && result.is_ok() {
+                let to_top = |v: i32| {
+                    i32::try_from((i64::from(v) * top).div_euclid(i64::from(group.grid))).ok()
+                };
+                for (mat, color_tiles) in &group.tiles {
+                    for (color, tiles) in color_tiles {
+                        for tile in tiles {
+                            let (min_pos, _) = tile.bounds();
+                            let (Some(x), Some(y), Some(z)) =
+                                (to_top(min_pos.x), to_top(min_pos.y), to_top(min_pos.z))
+                            else {
+                                result = Err(ParseError::Overflow("tile"));
+                                return;
+                            };
+                            items.push((group_index, mat, color, tile, LittlePos { x, y, z }));
+                        }
+                    }
+                }
+            }
+            group_index += 1;
+        });
+        result?;
+
+        // Morton 码要求非负坐标，整体平移到包围盒原点
+        let origin = items
+            .iter()
+            .map(|item| item.4)
+            .reduce(LittlePos::component_min)
+            .unwrap_or(LittlePos { x: 0, y: 0, z: 0 });
+        let mut keyed: Vec<_> = items
+            .into_iter()
+            .map(|(index, mat, color, tile, corner)| {
+                let code = morton_code([
+                    corner.x.abs_diff(origin.x),
+                    corner.y.abs_diff(origin.y),
+                    corner.z.abs_diff(origin.z),
+                ]);
+                (code, index, mat, color, tile, corner)
+            })
+            .collect();
+        keyed.sort_by(|a, b| {
+            (a.0, a.1, a.2, a.3, a.4.bounds()).cmp(&(b.0, b.1, b.2, b.3, b.4.bounds()))
+        });
+        Ok(keyed
+            .into_iter()
+            .map(|(_, _, mat, color, tile, corner)| (mat, color, tile, corner)))
+    }
+
+    /// 所有组精度的最小公倍数（公共精度）：把整棵树无损换算到同一精度所需的最小精度，
+    /// 体素化、统计与差异比较都以它为准。结果超出 `u16` 时返回 `Overflow("grid")`
+    pub fn lcm_grid(&self) -> Result<u16, ParseError> {
+        let mut grid = Ok(1);
+        self.top_group.for_each_group(&mut |group| {
+            if let (Ok(current), true) = (&grid, group.grid != 0) {
+                grid = checked_lcm(*current, group.grid);
+            }
+        });
+        grid
+    }
+
+    /// 按组的前序、组内按材质名与颜色排序（同一列表内保持原顺序），列出所有小方块换算到
+    /// `grid` 后的基础包围盒，`grid` 须为公共精度的倍数；坐标超出 i32 时返回 `Overflow("tile")`。
+    /// [`OccupancyMode::Visible`] 下跳过全透明的小方块
+    fn scaled_boxes(
+        &self,
+        grid: u32,
+        mode: OccupancyMode,
+    ) -> Result<Vec<(&Material, LittleColor, LittlePos, LittlePos)>, ParseError> {
+        let mut boxes = Vec::new();
+        let mut result = Ok(());
+        self.top_group.for_each_group(&mut |group| {
+            if group.grid == 0 || result.is_err() {
+                return;
+            }
+            let Ok(factor) = i32::try_from(grid / u32::from(group.grid)) else {
+                result = Err(ParseError::Overflow("grid"));
+                return;
+            };
+            let mut materials: Vec<_> = group.tiles.iter().collect();
+            materials.sort_by_key(|&(mat, _)| mat);
+            for (mat, color_tiles) in materials {
+                let mut colors: Vec<_> = color_tiles
+                    .iter()
+                    .filter(|(color, _)| mode == OccupancyMode::Geometric || color.a != 0)
+                    .collect();
+                colors.sort_by_key(|&(color, _)| color);
+                for (color, tiles) in colors {
+                    for tile in tiles {
+                        let (min_pos, max_pos) = tile.bounds();
+                        match (min_pos.checked_scale(factor), max_pos.checked_scale(factor)) {
+                            (Some(min_pos), Some(max_pos)) => {
+                                boxes.push((mat, *color, min_pos, max_pos))
+                            }
+                            _ => {
+                                result = Err(ParseError::Overflow("tile"));
+                                return;
+                            }
+                        }
+                    }
+                }
+            }
+        });
+        result.map(|()| boxes)
+    }
+
+    /// 按公共精度展开所有小方块的基础包围盒，`mode` 决定是否剔除全透明的小方块。
+    /// 得到的占用表可传给以下各项统计复用，避免重复光栅化；
+    /// 公共精度超出 `u16` 或坐标换算溢出时返回 `Overflow`
+    pub fn occupancy_map(&self, mode: OccupancyMode) -> Result<OccupancyMap<'_>, ParseError> {
+        self.occupancy_at(u32::from(self.lcm_grid()?), mode)
+    }
+
+    /// 在 `map` 上计算统计；未提供或模式不符时按 `mode` 重新光栅化
+    fn with_map<T>(
+        &self,
+        map: Option<&OccupancyMap<'_>>,
+        mode: OccupancyMode,
+        f: impl FnOnce(&OccupancyMap<'_>) -> T,
+    ) -> Result<T, ParseError> {
+        match map {
+            Some(map) if map.mode == mode => Ok(f(map)),
+            _ => Ok(f(&self.occupancy_map(mode)?)),
+        }
+    }
+
+    /// 被占据的单元格坐标（公共精度），按坐标排序
+    pub fn occupied_coords(
+        &self,
+        mode: OccupancyMode,
+        map: Option<&OccupancyMap<'_>>,
+    ) -> Result<Vec<LittlePos>, ParseError> {
+        self.with_map(map, mode, |map| map.occupied_coords())
+    }
+
+    /// 被占据的单元格数（公共精度），重叠部分只计一次
+    pub fn volume(
+        &self,
+        mode: OccupancyMode,
+        map: Option<&OccupancyMap<'_>>,
+    ) -> Result<u64, ParseError> {
+        self.with_map(map, mode, |map| map.volume())
+    }
+
+    /// 按指定精度展开，`grid` 须为公共精度的倍数
+    pub(crate) fn occupancy_at(
+        &self,
+        grid: u32,
+        mode: OccupancyMode,
+    ) -> Result<OccupancyMap<'_>, ParseError> {
+        let mut cells = HashMap::new();
+        for (mat, color, min_pos, max_pos) in self.scaled_boxes(grid, mode)? {
+            for x in min_pos.x..max_pos.x {
+                for y in min_pos.y..max_pos.y {
+                    for z in min_pos.z..max_pos.z {
+                        cells.insert(LittlePos { x, y, z }, (mat, color));
+                    }
+                }
+            }
+        }
+        Ok(OccupancyMap {
+            cells,
+            grid,
+            top_grid: self.top_group.grid.max(1),
+            mode,
+        })
+    }
+
+    /// 分块光栅化，避免一次性分配整个稠密网格。
+    /// 块按公共精度下 `chunk` 的整数倍对齐，`f` 依次收到包围盒范围内每个块的块坐标
+    /// （单元坐标整除 `chunk`）与该块的单元格，下标为 `x + cx * (y + cy * z)`；
+    /// 重叠时与 [`LittleBlueprint::slice`] 等一致，靠后的小方块覆盖靠前的。
+    /// `chunk` 含 0 时返回 `OutOfRange("chunk_size")`，块尺寸或块原点超出 i32 时返回 `Overflow`
+    pub fn for_each_chunk(
+        &self,
+        chunk: [u32; 3],
+        mut f: impl FnMut(LittlePos, &[Option<LittleColor>]),
+    ) -> Result<(), ParseError> {
+        if chunk.contains(&0) {
+            return Err(ParseError::OutOfRange("chunk_size"));
+        }
+        let cx = i32::try_from(chunk[0]).map_err(|_| ParseError::Overflow("chunk_size"))?;
+        let cy = i32::try_from(chunk[1]).map_err(|_| ParseError::Overflow("chunk_size"))?;
+        let cz = i32::try_from(chunk[2]).map_err(|_| ParseError::Overflow("chunk_size"))?;
+        let len = chunk
+            .iter()
+            .try_fold(1usize, |len, &c| len.checked_mul(c as usize))
+            .ok_or(ParseError::Overflow("chunk_size"))?;
+        let boxes = self.scaled_boxes(u32::from(self.lcm_grid()?), OccupancyMode::Geometric)?;
+        let non_empty = boxes
+            .iter()
+            .filter(|(_, _, min, max)| min.x < max.x && min.y < max.y && min.z < max.z);
+        let Some((lo, hi)) = non_empty.fold(None, |acc, &(_, _, min, max)| match acc {
+            None => Some((min, max)),
+            Some((lo, hi)) => Some((lo.component_min(min), hi.component_max(max))),
+        }) else {
+            return Ok(());
+        };
+        let mut cells = vec![None; len];
+        for iz in lo.z.div_euclid(cz)..=(hi.z - 1).div_euclid(cz) {
+            for iy in lo.y.div_euclid(cy)..=(hi.y - 1).div_euclid(cy) {
+                for ix in lo.x.div_euclid(cx)..=(hi.x - 1).div_euclid(cx) {
+                    let overflow = || ParseError::Overflow("chunk");
+                    let origin = LittlePos {
+                        x: ix.checked_mul(cx).ok_or_else(overflow)?,
+                        y: iy.checked_mul(cy).ok_or_else(overflow)?,
+                        z: iz.checked_mul(cz).ok_or_else(overflow)?,
+                    };
+                    let end = LittlePos {
+                        x: origin.x.checked_add(cx).ok_or_else(overflow)?,
+                        y: origin.y.checked_add(cy).ok_or_else(overflow)?,
+                        z: origin.z.checked_add(cz).ok_or_else(overflow)?,
+                    };
+                    cells.fill(None);
+                    for &(_, color, min, max) in &boxes {
+                        for z in min.z.max(origin.z)..max.z.min(end.z) {
+                            for y in min.y.max(origin.y)..max.y.min(end.y) {
+                                for x in min.x.max(origin.x)..max.x.min(end.x) {
+                                    let local = [x - origin.x, y - origin.y, z - origin.z]
+                                        .map(|v| v as usize);
+                                    let index = local[0]
+                                        + chunk[0] as usize
+                                            * (local[1] + chunk[1] as usize * local[2]);
+                                    cells[index] = Some(color);
+                                }
+                            }
+                        }
+                    }
+                    f(
+                        LittlePos {
+                            x: ix,
+                            y: iy,
+                            z: iz,
+                        },
+                        &cells,
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 不同材质的单元格相接的面，每个面只报告一次（取东、上、南方向），
+    /// 输出 `(单元格, 朝向, 本格材质, 相邻格材质)`；坐标为公共精度
+    pub fn material_interfaces(
+        &self,
+        map: Option<&OccupancyMap<'_>>,
+    ) -> Result<Vec<(LittlePos, Facing, Material, Material)>, ParseError> {
+        self.with_map(map, OccupancyMode::Geometric, |map| {
+            map.material_interfaces()
+        })
+    }
+
+    /// 每个单元格的六个相邻格都被占据、从外部完全不可见的材质（按公共精度判断）
+    pub fn enclosed_materials(
+        &self,
+        map: Option<&OccupancyMap<'_>>,
+    ) -> Result<HashSet<Material>, ParseError> {
+        self.with_map(map, OccupancyMode::Geometric, |map| {
+            map.enclosed_materials()
+        })
+    }
+
+    /// 包围球 `(球心, 半径)`，由被占据单元格的包围盒外接得到，单位为顶层精度；
+    /// 没有任何单元格时返回 None
+    pub fn bounding_sphere(
+        &self,
+        map: Option<&OccupancyMap<'_>>,
+    ) -> Result<Option<([f32; 3], f32)>, ParseError> {
+        self.with_map(map, OccupancyMode::Geometric, |map| map.bounding_sphere())
+    }
+
+    /// 沿 `axis` 正方向做正交投影，生成 `size * size` 的缩略图（行优先，下标 `v * size + u`，
+    /// 二维坐标约定同 [`LittleBlueprint::slice`]）。每个像素取深度最小（最靠近观察者）的单元格颜色，
+    /// 包围盒的长边缩放到整幅图，空像素为全透明
+    pub fn render_orthographic(
+        &self,
+        axis: Axis,
+        size: u32,
+        map: Option<&OccupancyMap<'_>>,
+    ) -> Result<Vec<LittleColor>, ParseError> {
+        self.with_map(map, OccupancyMode::Geometric, |map| {
+            map.render_orthographic(axis, size)
+        })
+    }
+
+    /// 沿 X、Y、Z 三个方向投影后被占据的二维单元格数（公共精度单位），
+    /// 投影坐标约定同 [`LittleBlueprint::slice`]
+    pub fn silhouette_areas(&self, map: Option<&OccupancyMap<'_>>) -> Result<[u64; 3], ParseError> {
+        self.with_map(map, OccupancyMode::Geometric, |map| map.silhouette_areas())
+    }
+
+    /// 取 `axis` 方向第 `layer` 层（公共精度单位）被占据的单元格，
+    /// 投影为二维坐标并附带颜色，按 `(u, v)` 排序；越界的层返回空
+    pub fn slice(
+        &self,
+        axis: Axis,
+        layer: i32,
+        map: Option<&OccupancyMap<'_>>,
+    ) -> Result<Vec<(i32, i32, LittleColor)>, ParseError> {
+        self.with_map(map, OccupancyMode::Geometric, |map| map.slice(axis, layer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::{blueprint_from_snbt, sample_blueprint};
+    use crate::{LittleBlueprintBuilder, LittleGroupBuilder};
+
+    #[test]
+    fn test_slice() {
+        let blueprint = sample_blueprint();
+        let white = LittleColor {
+            r: 255,
+            g: 255,
+            b: 255,
+            a: 255,
+        };
+        let footprint: Vec<_> = [
+            (0, 4),
+            (1, 5),
+            (2, 6),
+            (3, 3),
+            (3, 4),
+            (3, 7),
+            (4, 3),
+            (4, 4),
+        ]
+        .into_iter()
+        .map(|(u, v)| (u, v, white))
+        .collect();
+        assert_eq!(blueprint.slice(Axis::Y, 0, None).unwrap(), footprint);
+        assert!(blueprint.slice(Axis::Y, 1, None).unwrap().is_empty());
+        assert_eq!(blueprint.slice(Axis::X, 3, None).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_coord_convention() {
+        let blueprint = blueprint_from_snbt(
+            r#"
+    {
+        min: [I; 0, 0, 0],
+        size: [I; 1, 1, 1],
+        boxes: 1,
+        tiles: 1,
+        grid: 4,
+        c: [],
+        t: {
+            "minecraft:stone": [
+                [I; -1],
+                [I; 0, 0, 0, 1, 1, 1]
+            ]
+        }
+    }
+        "#,
+        );
+        let boxes = |convention| -> Vec<_> {
+            blueprint
+                .iter_tiles_absolute(convention)
+                .unwrap()
+                .map(|(_, _, min, max)| (min, max))
+                .collect()
+        };
+        assert_eq!(
+            boxes(CoordConvention::default()),
+            vec![([0.0; 3], [1.0; 3])]
+        );
+        assert_eq!(boxes(CoordConvention::Center), vec![([-0.5; 3], [0.5; 3])]);
+    }
+
+    #[test]
+    fn test_for_each_chunk() {
+        let blueprint = sample_blueprint();
+        let expected: HashMap<_, _> = blueprint
+            .occupancy_map(OccupancyMode::Geometric)
+            .unwrap()
+            .cells
+            .into_iter()
+            .map(|(pos, (_, color))| (pos, color))
+            .collect();
+
+        let chunk = [2, 1, 3];
+        let mut chunks = 0;
+        let mut reassembled = HashMap::new();
+        blueprint
+            .for_each_chunk(chunk, |index, cells| {
+                chunks += 1;
+                assert_eq!(cells.len(), 6);
+                for (i, cell) in cells.iter().enumerate() {
+                    let Some(color) = cell else { continue };
+                    let i = i as i32;
+                    let pos = LittlePos {
+                        x: index.x * 2 + i % 2,
+                        y: index.y,
+                        z: index.z * 3 + i / 2,
+                    };
+                    assert!(reassembled.insert(pos, *color).is_none());
+                }
+            })
+            .unwrap();
+        // x: 0..5 -> 3 块，z: 3..8 -> 块 1..=2
+        assert_eq!(chunks, 6);
+        assert_eq!(reassembled, expected);
+
+        // 块尺寸的校验与 into_chunks 一致
+        assert!(matches!(
+            blueprint.for_each_chunk([0, 1, 1], |_, _| {}),
+            Err(ParseError::OutOfRange("chunk_size"))
+        ));
+        assert!(matches!(
+            blueprint.for_each_chunk([u32::MAX, 1, 1], |_, _| {}),
+            Err(ParseError::Overflow("chunk_size"))
+        ));
+
+        // 最后一个块的末端超出 i32：返回错误而不是回绕
+        let near_max = LittleTile::try_from(vec![i32::MAX - 1, 0, 0, i32::MAX, 1, 1]).unwrap();
+        let top = LittleGroupBuilder::new()
+            .grid(1)
+            .add_tile(
+                "minecraft:stone",
+                LittleColor::try_from(-1).unwrap(),
+                near_max,
+            )
+            .build();
+        let blueprint = LittleBlueprintBuilder::new(top).build();
+        assert!(matches!(
+            blueprint.for_each_chunk([2, 1, 1], |_, _| {}),
+            Err(ParseError::Overflow("chunk"))
+        ));
+    }
+
+    #[test]
+    fn test_material_interfaces() {
+        let blueprint = blueprint_from_snbt(
+            r#"
+    {
+        min: [I; 0, 0, 0],
+        size: [I; 2, 1, 1],
+        boxes: 2,
+        tiles: 2,
+        grid: 4,
+        c: [],
+        t: {
+            "minecraft:glass": [
+                [I; -1],
+                [I; 0, 0, 0, 1, 1, 1]
+            ],
+            "minecraft:stone": [
+                [I; -1],
+                [I; 1, 0, 0, 2, 1, 1]
+            ]
+        }
+    }
+        "#,
+        );
+        assert_eq!(
+            blueprint.material_interfaces(None).unwrap(),
+            vec![(
+                LittlePos { x: 0, y: 0, z: 0 },
+                Facing::East,
+                "minecraft:glass".to_string(),
+                "minecraft:stone".to_string()
+            )]
+        );
+        assert!(
+            sample_blueprint()
+                .material_interfaces(None)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_bounding_sphere() {
+        let blueprint = blueprint_from_snbt(
+            r#"
+    {
+        min: [I; 0, 0, 0],
+        size: [I; 1, 1, 1],
+        boxes: 1,
+        tiles: 1,
+        grid: 4,
+        c: [],
+        t: {
+            "minecraft:stone": [
+                [I; -1],
+                [I; 2, 0, 0, 3, 1, 1]
+            ]
+        }
+    }
+        "#,
+        );
+        let (center, radius) = blueprint.bounding_sphere(None).unwrap().unwrap();
+        assert_eq!(center, [2.5, 0.5, 0.5]);
+        assert!((radius - 3f32.sqrt() / 2.0).abs() < 1e-6);
+
+        // 子组精度更高时，结果仍换算回顶层精度
+        let blueprint = blueprint_from_snbt(
+            r#"
+    {
+        min: [I; 2, 0, 0],
+        size: [I; 1, 1, 1],
+        boxes: 1,
+        tiles: 1,
+        grid: 4,
+        c: [
+            {
+                grid: 8,
+                c: [],
+                t: {
+                    "minecraft:stone": [
+                        [I; -1],
+                        [I; 4, 0, 0, 5, 1, 1]
+                    ]
+                }
+            }
+        ],
+        t: {}
+    }
+        "#,
+        );
+        assert_eq!(blueprint.lcm_grid().unwrap(), 8);
+        let (center, radius) = blueprint.bounding_sphere(None).unwrap().unwrap();
+        assert_eq!(center, [2.25, 0.25, 0.25]);
+        assert!((radius - 3f32.sqrt() / 4.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_lcm_grid() {
+        let mut blueprint = sample_blueprint();
+        blueprint.top_group.children[1].grid = 8;
+        assert_eq!(blueprint.lcm_grid().unwrap(), 8);
+
+        blueprint.top_group.children[0].grid = 65535;
+        assert!(blueprint.lcm_grid().is_err());
+        // 依赖公共精度的查询同样报错而不是 panic
+        assert!(matches!(
+            blueprint.slice(Axis::Y, 0, None),
+            Err(ParseError::Overflow("grid"))
+        ));
+    }
+
+    #[test]
+    fn test_coprime_grids_overflow() {
+        let overflow = |err| matches!(err, ParseError::Overflow("grid"));
+        let mut blueprint = sample_blueprint();
+        blueprint.top_group.grid = 65533;
+        blueprint.top_group.children[0].grid = 65535;
+        blueprint.top_group.children[1].grid = 65534;
+        assert!(overflow(blueprint.lcm_grid().unwrap_err()));
+        assert!(overflow(
+            blueprint
+                .volume(OccupancyMode::Geometric, None)
+                .unwrap_err()
+        ));
+        assert!(overflow(blueprint.slice(Axis::Y, 0, None).unwrap_err()));
+        assert!(overflow(blueprint.stats().unwrap_err()));
+        assert!(overflow(
+            blueprint.for_each_chunk([4; 3], |_, _| {}).unwrap_err()
+        ));
+
+        // 精度本身不溢出，但换算后的坐标超出 i32
+        let far = LittleTile::try_from(vec![1 << 20, 0, 0, (1 << 20) + 1, 1, 1]).unwrap();
+        let top = LittleGroupBuilder::new()
+            .grid(1)
+            .add_tile("minecraft:stone", LittleColor::try_from(-1).unwrap(), far)
+            .add_child(LittleGroupBuilder::new().grid(65535).build())
+            .build();
+        let blueprint = LittleBlueprintBuilder::new(top).build();
+        assert!(matches!(
+            blueprint
+                .volume(OccupancyMode::Geometric, None)
+                .unwrap_err(),
+            ParseError::Overflow("tile")
+        ));
+    }
+
+    #[test]
+    fn test_render_orthographic() {
+        let blueprint = blueprint_from_snbt(
+            r#"
+    {
+        min: [I; 0, 0, 0],
+        size: [I; 2, 2, 2],
+        boxes: 2,
+        tiles: 2,
+        grid: 4,
+        c: [],
+        t: {
+            "minecraft:red_wool": [
+                [I; -16776961],
+                [I; 0, 0, 0, 2, 1, 2]
+            ],
+            "minecraft:blue_wool": [
+                [I; 65535],
+                [I; 0, 1, 0, 2, 2, 1]
+            ]
+        }
+    }
+        "#,
+        );
+        let red = LittleColor::try_from(-16776961).unwrap();
+        let blue = LittleColor::try_from(65535).unwrap();
+        assert_eq!((red.r, red.b, blue.r, blue.b), (255, 0, 0, 255));
+
+        // 从下往上看只能看到红色
+        let bottom = blueprint.render_orthographic(Axis::Y, 4, None).unwrap();
+        assert_eq!(bottom.len(), 16);
+        assert!(bottom.iter().all(|&c| c == red));
+
+        // 沿 Z 看：蓝色在前挡住上半部分的红色
+        let front = blueprint.render_orthographic(Axis::Z, 2, None).unwrap();
+        assert_eq!(front, vec![red, red, blue, blue]);
+
+        assert_eq!(
+            blueprint.render_orthographic(Axis::X, 1, None).unwrap(),
+            vec![red]
+        );
+        assert!(
+            sample_blueprint()
+                .render_orthographic(Axis::Y, 10, None)
+                .unwrap()
+                .contains(&LittleColor::default())
+        );
+    }
+
+    #[test]
+    fn test_iter_tiles_morton() {
+        assert_eq!(morton_code([1, 0, 0]), 1);
+        assert_eq!(morton_code([0, 1, 0]), 2);
+        assert_eq!(morton_code([0, 0, 1]), 4);
+        assert_eq!(morton_code([3, 0, 1]), 0b1101);
+
+        let blueprint = sample_blueprint();
+        let corners: Vec<LittlePos> = blueprint
+            .iter_tiles_morton()
+            .unwrap()
+            .map(|t| t.3)
+            .collect();
+        assert_eq!(corners.len(), 8);
+
+        // 参考实现：逐位比较最高的不同位所在的轴
+        let mut reference = corners.clone();
+        reference.sort_by(|a, b| {
+            let (a, b) = ([a.x, a.y, a.z - 3], [b.x, b.y, b.z - 3]);
+            // 最高位相同时 Z 优先于 Y、X
+            let mut axis = 2;
+            let mut highest = (a[2] ^ b[2]) as u32;
+            for i in [1, 0] {
+                let diff = (a[i] ^ b[i]) as u32;
+                if highest < diff && highest < (highest ^ diff) {
+                    highest = diff;
+                    axis = i;
+                }
+            }
+            a[axis].cmp(&b[axis])
+        });
+        assert_eq!(corners, reference);
+        assert_eq!(corners[0], LittlePos { x: 0, y: 0, z: 4 });
+
+        let again: Vec<LittlePos> = blueprint
+            .iter_tiles_morton()
+            .unwrap()
+            .map(|t| t.3)
+            .collect();
+        assert_eq!(again, corners);
+
+        // 角点跨度超出 i32 时仍能求出相对原点的偏移
+        let stone = LittleColor::try_from(-1).unwrap();
+        let tile = |x: i32| LittleTile::try_from(vec![x, 0, 0, x + 1, 1, 1]).unwrap();
+        let top = LittleGroupBuilder::new()
+            .grid(1)
+            .add_tile("minecraft:stone", stone, tile(i32::MAX - 1))
+            .add_tile("minecraft:stone", stone, tile(i32::MIN))
+            .build();
+        let wide = LittleBlueprintBuilder::new(top).build();
+        let corners: Vec<i32> = wide.iter_tiles_morton().unwrap().map(|t| t.3.x).collect();
+        assert_eq!(corners, vec![i32::MIN, i32::MAX - 1]);
+
+        // 子组比顶层粗，换算到顶层精度后超出 i32
+        let top = LittleGroupBuilder::new()
+            .grid(16)
+            .add_child(
+                LittleGroupBuilder::new()
+                    .grid(1)
+                    .add_tile("minecraft:stone", stone, tile(1 << 28))
+                    .build(),
+            )
+            .build();
+        let coarse = LittleBlueprintBuilder::new(top).build();
+        assert!(matches!(
+            coarse.iter_tiles_morton().map(|tiles| tiles.count()),
+            Err(ParseError::Overflow("tile"))
+        ));
+    }
+
+    #[test]
+    fn test_silhouette_areas() {
+        let blueprint = blueprint_from_snbt(
+            r#"
+    {
+        min: [I; 0, 0, 0],
+        size: [I; 2, 1, 3],
+        boxes: 1,
+        tiles: 1,
+        grid: 1,
+        c: [],
+        t: {
+            "minecraft:stone": [
+                [I; -1],
+                [I; 0, 0, 0, 2, 1, 3]
+            ]
+        }
+    }
+        "#,
+        );
+        assert_eq!(blueprint.silhouette_areas(None).unwrap(), [3, 6, 2]);
+        // 示例蓝图只有一层，俯视面积即单元格数
+        assert_eq!(sample_blueprint().silhouette_areas(None).unwrap()[1], 8);
+    }
+
+    #[test]
+    fn test_enclosed_materials() {
+        // 3x3x3 的石头外壳包住中心的一格玻璃
+        let blueprint = blueprint_from_snbt(
+            r#"
+    {
+        min: [I; 0, 0, 0],
+        size: [I; 3, 3, 3],
+        boxes: 7,
+        tiles: 2,
+        grid: 1,
+        c: [],
+        t: {
+            "minecraft:stone": [
+                [I; -1],
+                [I; 0, 0, 0, 3, 1, 3],
+                [I; 0, 2, 0, 3, 3, 3],
+                [I; 0, 1, 0, 3, 2, 1],
+                [I; 0, 1, 2, 3, 2, 3],
+                [I; 0, 1, 1, 1, 2, 2],
+                [I; 2, 1, 1, 3, 2, 2]
+            ],
+            "minecraft:glass": [
+                [I; -1],
+                [I; 1, 1, 1, 2, 2, 2]
+            ]
+        }
+    }
+        "#,
+        );
+        let enclosed = blueprint.enclosed_materials(None).unwrap();
+        assert_eq!(enclosed, HashSet::from(["minecraft:glass".to_string()]));
+        assert!(
+            sample_blueprint()
+                .enclosed_materials(None)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_occupancy_mode() {
+        let blueprint = blueprint_from_snbt(
+            r#"
+    {
+        min: [I; 0, 0, 0],
+        size: [I; 3, 1, 1],
+        boxes: 2,
+        tiles: 2,
+        grid: 1,
+        c: [],
+        t: {
+            "minecraft:stone": [
+                [I; -1],
+                [I; 0, 0, 0, 2, 1, 1]
+            ],
+            "minecraft:glass": [
+                [I; -256],
+                [I; 2, 0, 0, 3, 1, 1]
+            ]
+        }
+    }
+        "#,
+        );
+        assert_eq!(blueprint.volume(OccupancyMode::default(), None).unwrap(), 3);
+        assert_eq!(blueprint.volume(OccupancyMode::Visible, None).unwrap(), 2);
+        assert_eq!(
+            blueprint
+                .occupied_coords(OccupancyMode::Visible, None)
+                .unwrap(),
+            vec![
+                LittlePos { x: 0, y: 0, z: 0 },
+                LittlePos { x: 1, y: 0, z: 0 }
+            ]
+        );
+        assert_eq!(
+            sample_blueprint()
+                .volume(OccupancyMode::Visible, None)
+                .unwrap(),
+            8
+        );
+
+        // 全透明的玻璃与石头重叠并在几何占用中胜出，可见占用仍保留下面的石头
+        let blueprint = blueprint_from_snbt(
+            r#"
+    {
+        min: [I; 0, 0, 0],
+        size: [I; 2, 1, 1],
+        boxes: 2,
+        tiles: 2,
+        grid: 1,
+        c: [],
+        t: {
+            "minecraft:stone": [
+                [I; -1],
+                [I; 0, 0, 0, 2, 1, 1]
+            ],
+            "minecraft:white_stained_glass": [
+                [I; -256],
+                [I; 1, 0, 0, 2, 1, 1]
+            ]
+        }
+    }
+        "#,
+        );
+        let cell = LittlePos { x: 1, y: 0, z: 0 };
+        let geometric = blueprint.occupancy_map(OccupancyMode::Geometric).unwrap();
+        assert_eq!(geometric.cells[&cell].0, "minecraft:white_stained_glass");
+        let visible = blueprint.occupancy_map(OccupancyMode::Visible).unwrap();
+        assert_eq!(visible.cells[&cell].0, "minecraft:stone");
+        assert_eq!(blueprint.volume(OccupancyMode::Visible, None).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_shared_occupancy_map() {
+        // 子组精度高于顶层，另有一个全透明的小方块，两种模式下的所有统计都应一致
+        let mut blueprint = sample_blueprint();
+        blueprint.top_group.children[1].grid = 8;
+        blueprint
+            .top_group
+            .tiles
+            .entry("minecraft:white_stained_glass".to_string())
+            .or_default()
+            .insert(
+                LittleColor::try_from(-256).unwrap(),
+                vec![LittleTile::try_from(vec![0, 1, 0, 2, 2, 2]).unwrap()],
+            );
+        assert_eq!(blueprint.lcm_grid().unwrap(), 8);
+
+        let map = blueprint.occupancy_map(OccupancyMode::Geometric).unwrap();
+        let shared = Some(&map);
+        assert_eq!(
+            blueprint.bounding_sphere(shared).unwrap(),
+            blueprint.bounding_sphere(None).unwrap()
+        );
+        assert_eq!(
+            blueprint.silhouette_areas(shared).unwrap(),
+            blueprint.silhouette_areas(None).unwrap()
+        );
+        assert_eq!(
+            blueprint.enclosed_materials(shared).unwrap(),
+            blueprint.enclosed_materials(None).unwrap()
+        );
+        assert_eq!(
+            blueprint.material_interfaces(shared).unwrap(),
+            blueprint.material_interfaces(None).unwrap()
+        );
+        for axis in Axis::ALL {
+            assert_eq!(
+                blueprint.render_orthographic(axis, 4, shared).unwrap(),
+                blueprint.render_orthographic(axis, 4, None).unwrap()
+            );
+            assert_eq!(
+                blueprint.slice(axis, 1, shared).unwrap(),
+                blueprint.slice(axis, 1, None).unwrap()
+            );
+        }
+
+        let visible = blueprint.occupancy_map(OccupancyMode::Visible).unwrap();
+        assert!(visible.cells.len() < map.cells.len());
+        for (mode, map) in [
+            (OccupancyMode::Geometric, &map),
+            (OccupancyMode::Visible, &visible),
+        ] {
+            assert_eq!(
+                blueprint.volume(mode, Some(map)).unwrap(),
+                blueprint.volume(mode, None).unwrap()
+            );
+            assert_eq!(
+                blueprint.occupied_coords(mode, Some(map)).unwrap(),
+                blueprint.occupied_coords(mode, None).unwrap()
+            );
+        }
+        // 模式不符的占用表不会被误用
+        assert_eq!(
+            blueprint
+                .volume(OccupancyMode::Geometric, Some(&visible))
+                .unwrap(),
+            map.cells.len() as u64
+        );
+    }
+}
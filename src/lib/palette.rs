@@ -0,0 +1,108 @@
+use crate::{ColorTiles, LittleBlueprint, LittleColor, LittleGroup};
+use std::collections::HashMap;
+
+impl LittleColor {
+    /// RGBA 四个通道的差值都不超过 `tolerance`
+    pub fn within(self, other: LittleColor, tolerance: u8) -> bool {
+        self.r.abs_diff(other.r) <= tolerance
+            && self.g.abs_diff(other.g) <= tolerance
+            && self.b.abs_diff(other.b) <= tolerance
+            && self.a.abs_diff(other.a) <= tolerance
+    }
+}
+
+impl LittleGroup {
+    /// 合并同一材质下近似的颜色分组（各通道差值均不超过 `tolerance`），递归处理子组。
+    /// 小方块最多的颜色优先成为代表色，被合并的小方块追加到代表色之后
+    pub fn quantize_colors(&mut self, tolerance: u8) {
+        for color_tiles in self.tiles.values_mut() {
+            let mut buckets: Vec<_> = std::mem::take(color_tiles).into_iter().collect();
+            // 数量相同时按打包值排序，保证结果确定
+            buckets.sort_by_key(|(color, tiles)| {
+                let packed: i32 = (*color).try_into().unwrap_or_default();
+                (std::cmp::Reverse(tiles.len()), packed)
+            });
+            let mut merged: Vec<(LittleColor, Vec<_>)> = Vec::new();
+            for (color, tiles) in buckets {
+                match merged
+                    .iter_mut()
+                    .find(|(rep, _)| rep.within(color, tolerance))
+                {
+                    Some((_, rep_tiles)) => rep_tiles.extend(tiles),
+                    None => merged.push((color, tiles)),
+                }
+            }
+            *color_tiles = merged.into_iter().collect::<ColorTiles>();
+        }
+        for child in &mut self.children {
+            child.quantize_colors(tolerance);
+        }
+    }
+}
+
+impl LittleBlueprint {
+    /// 整棵组树中出现过的颜色（按颜色排序去重）及颜色到调色板下标的映射，
+    /// 可直接作为渲染端的调色板上传
+    pub fn palette(&self) -> (Vec<LittleColor>, HashMap<LittleColor, u32>) {
+        let mut colors = Vec::new();
+        self.top_group.for_each_group(&mut |group| {
+            for color_tiles in group.tiles.values() {
+                colors.extend(color_tiles.keys().copied());
+            }
+        });
+        colors.sort();
+        colors.dedup();
+        let index = colors
+            .iter()
+            .enumerate()
+            .map(|(i, color)| (*color, i as u32))
+            .collect();
+        (colors, index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_data::sample_blueprint;
+    use crate::{LittleColor, LittleTile};
+
+    #[test]
+    fn test_palette() {
+        let mut blueprint = sample_blueprint();
+        let (colors, index) = blueprint.palette();
+        assert_eq!(colors, vec![LittleColor::try_from(-1).unwrap()]);
+        assert_eq!(index.len(), 1);
+
+        let red = LittleColor::try_from(-16776961).unwrap();
+        let tile = LittleTile::try_from(vec![0, 0, 0, 1, 1, 1]).unwrap();
+        blueprint.top_group.children[1].children[0]
+            .tiles
+            .get_mut("minecraft:purple_wool")
+            .unwrap()
+            .insert(red, vec![tile]);
+        let (colors, index) = blueprint.palette();
+        assert_eq!(colors.len(), 2);
+        assert_eq!(colors[index[&red] as usize], red);
+    }
+
+    #[test]
+    fn test_quantize_colors() {
+        let mut blueprint = sample_blueprint();
+        let white = LittleColor::try_from(-1).unwrap();
+        let off_white = LittleColor { b: 254, ..white };
+        let far = LittleColor { b: 250, ..white };
+        let stone = blueprint.top_group.children[0]
+            .tiles
+            .get_mut("minecraft:stone")
+            .unwrap();
+        let tile = LittleTile::try_from(vec![0, 0, 0, 1, 1, 1]).unwrap();
+        stone.insert(off_white, vec![tile.clone()]);
+        stone.insert(far, vec![tile]);
+
+        blueprint.top_group.quantize_colors(2);
+        let stone = &blueprint.top_group.children[0].tiles["minecraft:stone"];
+        assert_eq!(stone.len(), 2);
+        assert_eq!(stone[&white].len(), 5);
+        assert_eq!(stone[&far].len(), 1);
+    }
+}
@@ -0,0 +1,106 @@
+use crate::LittleBlueprint;
+use quartz_nbt::{NbtCompound, NbtTag};
+use std::collections::BTreeSet;
+
+impl LittleBlueprint {
+    /// 解析后再序列化，检查结果与 `nbt` 完全一致；不一致时报告第一个不同之处的路径，
+    /// 形如 `c[1].t["minecraft:red_wool"][2]`，便于定位颜色标记、顺序等往返问题
+    pub fn roundtrip_check(nbt: &NbtCompound) -> Result<(), String> {
+        let blueprint =
+            LittleBlueprint::try_from(nbt.clone()).map_err(|err| format!("parse: {err}"))?;
+        let written: NbtCompound = blueprint
+            .try_into()
+            .map_err(|err| format!("serialize: {err}"))?;
+        match compound_difference(nbt, &written, String::new()) {
+            Some(diff) => Err(diff),
+            None => Ok(()),
+        }
+    }
+}
+
+fn key_path(path: &str, key: &str) -> String {
+    let plain = !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    match (plain, path.is_empty()) {
+        (true, true) => key.to_string(),
+        (true, false) => format!("{path}.{key}"),
+        (false, _) => format!("{path}[{key:?}]"),
+    }
+}
+
+fn compound_difference(before: &NbtCompound, after: &NbtCompound, path: String) -> Option<String> {
+    // 按键名排序，保证报告的位置稳定
+    let keys: BTreeSet<&String> = before.inner().keys().chain(after.inner().keys()).collect();
+    keys.into_iter().find_map(|key| {
+        let path = key_path(&path, key);
+        match (before.inner().get(key), after.inner().get(key)) {
+            (Some(a), Some(b)) => tag_difference(a, b, path),
+            (Some(_), None) => Some(format!("{path}: missing after round-trip")),
+            (None, _) => Some(format!("{path}: added by round-trip")),
+        }
+    })
+}
+
+fn seq_difference<'a, T: PartialEq + 'a>(
+    before: &'a [T],
+    after: &'a [T],
+    path: &str,
+    inner: impl Fn(&'a T, &'a T, String) -> Option<String>,
+) -> Option<String> {
+    before
+        .iter()
+        .zip(after)
+        .enumerate()
+        .find_map(|(i, (a, b))| inner(a, b, format!("{path}[{i}]")))
+        .or_else(|| {
+            (before.len() != after.len())
+                .then(|| format!("{path}: length {} became {}", before.len(), after.len()))
+        })
+}
+
+fn tag_difference(before: &NbtTag, after: &NbtTag, path: String) -> Option<String> {
+    if before == after {
+        return None;
+    }
+    let leaf = |a: &i32, b: &i32, path: String| (a != b).then(|| format!("{path}: {a} became {b}"));
+    let found = match (before, after) {
+        (NbtTag::Compound(a), NbtTag::Compound(b)) => compound_difference(a, b, path.clone()),
+        (NbtTag::List(a), NbtTag::List(b)) => {
+            let a: Vec<&NbtTag> = a.iter().collect();
+            let b: Vec<&NbtTag> = b.iter().collect();
+            seq_difference(&a, &b, &path, |a, b, path| tag_difference(a, b, path))
+        }
+        (NbtTag::IntArray(a), NbtTag::IntArray(b)) => seq_difference(a, b, &path, leaf),
+        _ => None,
+    };
+    found.or_else(|| Some(format!("{path}: {before} became {after}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::SAMPLE_SNBT;
+    use quartz_nbt::snbt;
+
+    #[test]
+    fn test_roundtrip_check() {
+        let root = snbt::parse(SAMPLE_SNBT).unwrap();
+        assert_eq!(LittleBlueprint::roundtrip_check(&root), Ok(()));
+
+        // 首个颜色标记之前的小方块，重新序列化时会补上默认颜色的标记
+        let perturbed = SAMPLE_SNBT.replace(
+            r#""minecraft:red_wool": ["#,
+            r#""minecraft:red_wool": [[I; 2, 0, 6, 3, 1, 7], "#,
+        );
+        let root = snbt::parse(&perturbed).unwrap();
+        let err = LittleBlueprint::roundtrip_check(&root).unwrap_err();
+        assert!(
+            err.starts_with(r#"c[1].t["minecraft:red_wool"][0]"#),
+            "{err}"
+        );
+
+        assert_eq!(
+            key_path("c[0]", "minecraft:stone"),
+            r#"c[0]["minecraft:stone"]"#
+        );
+    }
+}
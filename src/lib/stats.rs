@@ -0,0 +1,71 @@
+use crate::{LittleBlueprint, LittleColor, ParseError};
+use std::collections::HashSet;
+
+/// 蓝图的概要统计，见 [`LittleBlueprint::stats`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BlueprintStats {
+    /// 盒子总数，与 [`LittleBlueprint::count_tiles`] 的 boxes 相同
+    pub boxes: u32,
+    /// 小方块总数，与 [`LittleBlueprint::count_tiles`] 的 tiles 相同
+    pub tiles: u32,
+    /// 各盒子基础包围盒体积之和（公共精度单元），重叠部分重复计算
+    pub volume: u64,
+    pub materials: usize,
+    pub colors: usize,
+    /// 组树的最大嵌套深度，只有顶层组时为 1
+    pub depth: usize,
+}
+
+impl LittleBlueprint {
+    /// 不做体素化的快速统计：体积直接累加盒子尺寸，变换盒按基础包围盒计算；
+    /// 公共精度超出 `u16` 时返回 `Overflow("grid")`
+    pub fn stats(&self) -> Result<BlueprintStats, ParseError> {
+        let grid = u64::from(self.lcm_grid()?);
+        let (boxes, tiles) = self.count_tiles();
+        let mut volume = 0u64;
+        let mut materials = HashSet::new();
+        let mut colors: HashSet<LittleColor> = HashSet::new();
+        self.top_group.for_each_group(&mut |group| {
+            if group.grid == 0 {
+                return;
+            }
+            let scale = (grid / u64::from(group.grid)).pow(3);
+            for (mat, color_tiles) in &group.tiles {
+                materials.insert(mat);
+                colors.extend(color_tiles.keys().copied());
+                let sum: u64 = color_tiles.values().flatten().map(|t| t.volume()).sum();
+                volume = volume.saturating_add(sum.saturating_mul(scale));
+            }
+        });
+        Ok(BlueprintStats {
+            boxes,
+            tiles,
+            volume,
+            materials: materials.len(),
+            colors: colors.len(),
+            depth: self.top_group.depth(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::sample_blueprint;
+
+    #[test]
+    fn test_stats() {
+        let stats = sample_blueprint().stats().unwrap();
+        assert_eq!(
+            stats,
+            BlueprintStats {
+                boxes: 8,
+                tiles: 5,
+                volume: 8,
+                materials: 5,
+                colors: 1,
+                depth: 4,
+            }
+        );
+    }
+}
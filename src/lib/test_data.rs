@@ -0,0 +1,88 @@
+use crate::LittleBlueprint;
+use quartz_nbt::snbt;
+
+/// README 中的示例蓝图，供各模块测试复用
+pub(crate) const SAMPLE_SNBT: &str = r#"
+    {
+        min: [I; 0, 0, 3],
+        c: [
+            {
+                s: {
+                    id: "fixed"
+                },
+                c: [],
+                t: {
+                    "minecraft:stone": [
+                        [I; -1],
+                        [I; 3, 0, 3, 4, 1, 4],
+                        [I; 3, 0, 4, 4, 1, 5],
+                        [I; 4, 0, 3, 5, 1, 4],
+                        [I; 4, 0, 4, 5, 1, 5]
+                    ]
+                },
+                grid: 4
+            },
+            {
+                t: {
+                    "minecraft:red_wool": [
+                        [I; -1],
+                        [I; 2, 0, 6, 3, 1, 7]
+                    ]
+                },
+                c: [
+                    {
+                        c: [
+                            {
+                                grid: 4,
+                                s: {
+                                    id: "fixed"
+                                },
+                                c: [],
+                                t: {
+                                    "minecraft:lime_wool": [
+                                        [I; -1],
+                                        [I; 0, 0, 4, 1, 1, 5]
+                                    ]
+                                }
+                            }
+                        ],
+                        t: {
+                            "minecraft:purple_wool": [
+                                [I; -1],
+                                [I; 1, 0, 5, 2, 1, 6]
+                            ]
+                        },
+                        grid: 4,
+                        s: {
+                            id: "fixed"
+                        }
+                    }
+                ],
+                grid: 4,
+                s: {
+                    id: "fixed"
+                }
+            }
+        ],
+        boxes: 8,
+        tiles: 5,
+        grid: 4,
+        t: {
+            "minecraft:white_wool": [
+                [I; -1],
+                [I; 3, 0, 7, 4, 1, 8]
+            ]
+        },
+        size: [I; 5, 1, 5]
+    }
+"#;
+
+pub(crate) fn sample_blueprint() -> LittleBlueprint {
+    blueprint_from_snbt(SAMPLE_SNBT)
+}
+
+/// 由 SNBT 文本直接构造蓝图
+pub(crate) fn blueprint_from_snbt(snbt: &str) -> LittleBlueprint {
+    let root = snbt::parse(snbt).expect("Failed to parse SNBT");
+    LittleBlueprint::try_from(root).expect("Failed to convert SNBT to LittleBlueprint")
+}
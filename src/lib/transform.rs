@@ -0,0 +1,493 @@
+use crate::occupancy::checked_lcm;
+use crate::{
+    Axis, LittleBlueprint, LittleGroup, LittlePos, LittleStructure, LittleTile, MaterialTiles,
+    ParseError,
+};
+use enum_map::EnumMap;
+
+/// 将 `grid` 精度下的单个坐标换算到 `target` 精度，不能整除时报错，溢出时报告 `field`
+fn convert_value(v: i32, grid: u16, target: u16, field: &'static str) -> Result<i32, ParseError> {
+    let scaled = i64::from(v) * i64::from(target);
+    if grid == 0 || scaled % i64::from(grid) != 0 {
+        return Err(ParseError::IncompatibleGrid { grid, target });
+    }
+    i32::try_from(scaled / i64::from(grid)).map_err(|_| ParseError::Overflow(field))
+}
+
+fn convert_pos(
+    pos: LittlePos,
+    grid: u16,
+    target: u16,
+    field: &'static str,
+) -> Result<LittlePos, ParseError> {
+    Ok(LittlePos {
+        x: convert_value(pos.x, grid, target, field)?,
+        y: convert_value(pos.y, grid, target, field)?,
+        z: convert_value(pos.z, grid, target, field)?,
+    })
+}
+
+/// 将 `grid` 精度下的位移换算到 `target` 精度，不能整除时报错
+fn convert_delta(delta: LittlePos, grid: u16, target: u16) -> Result<LittlePos, ParseError> {
+    if grid == target {
+        return Ok(delta);
+    }
+    convert_pos(delta, grid, target, "delta")
+}
+
+impl LittleGroup {
+    /// 平移本组及所有子组的小方块，`delta` 以本组精度为单位，子组按各自精度换算；
+    /// 变换盒的角点偏移是相对量，保持不变。出错时不做任何修改
+    pub fn translate(&mut self, delta: LittlePos) -> Result<(), ParseError> {
+        let mut moved = self.clone();
+        moved.shift(delta)?;
+        *self = moved;
+        Ok(())
+    }
+
+    /// 把各轴精度不同（`grid_x`、`grid_y`、`grid_z`）的小方块换算到三者的最小公倍数这一统一精度，
+    /// 生成没有子组的组。换算总是整倍数放大，精度超出 `u16` 或坐标、角点偏移溢出时报错
+    pub fn from_anisotropic(
+        mut tiles: MaterialTiles,
+        grid_x: u16,
+        grid_y: u16,
+        grid_z: u16,
+    ) -> Result<LittleGroup, ParseError> {
+        let grids = EnumMap::from_array([grid_x, grid_y, grid_z]);
+        if grids.values().any(|&grid| grid == 0) {
+            return Err(ParseError::OutOfRange("grid"));
+        }
+        let grid = grids
+            .values()
+            .try_fold(1, |acc, &grid| checked_lcm(acc, grid))?;
+        let factors = grids.map(|_, axis_grid| i32::from(grid / axis_grid));
+
+        for tile in tiles.values_mut().flat_map(|c| c.values_mut()).flatten() {
+            let (min_pos, max_pos) = tile.bounds_mut();
+            for pos in [min_pos, max_pos] {
+                for axis in Axis::ALL {
+                    let value = pos.get_mut(axis);
+                    *value = value
+                        .checked_mul(factors[axis])
+                        .ok_or(ParseError::Overflow("tile"))?;
+                }
+            }
+            if let LittleTile::TransformableBox { corner, .. } = tile {
+                for offsets in corner.values_mut() {
+                    for (axis, offset) in offsets.iter_mut() {
+                        *offset = i32::from(*offset)
+                            .checked_mul(factors[axis])
+                            .and_then(|v| i16::try_from(v).ok())
+                            .ok_or(ParseError::Overflow("corner"))?;
+                    }
+                }
+            }
+        }
+        Ok(LittleGroup {
+            grid,
+            children: Vec::new(),
+            tiles,
+            structure: None,
+            extension: None,
+            format: Default::default(),
+        })
+    }
+
+    /// 把本组换算到 `new_grid` 精度：小方块坐标与变换盒角点偏移按 `new_grid / grid` 缩放，
+    /// 与本组精度相同的子组一并换算，其它子组保持不变。变粗时坐标必须能整除，
+    /// 否则返回 [`ParseError::IncompatibleGrid`]；出错时不做任何修改
+    pub fn regrid(&mut self, new_grid: u16) -> Result<(), ParseError> {
+        if new_grid == 0 {
+            return Err(ParseError::OutOfRange("grid"));
+        }
+        let mut converted = self.clone();
+        converted.regrid_from(self.grid, new_grid)?;
+        *self = converted;
+        Ok(())
+    }
+
+    fn regrid_from(&mut self, grid: u16, new_grid: u16) -> Result<(), ParseError> {
+        if self.grid != grid {
+            return Ok(());
+        }
+        for tile in self
+            .tiles
+            .values_mut()
+            .flat_map(|c| c.values_mut())
+            .flatten()
+        {
+            let (min_pos, max_pos) = tile.bounds_mut();
+            *min_pos = convert_pos(*min_pos, grid, new_grid, "tile")?;
+            *max_pos = convert_pos(*max_pos, grid, new_grid, "tile")?;
+            if let LittleTile::TransformableBox { corner, .. } = tile {
+                for offset in corner.values_mut().flat_map(|axes| axes.values_mut()) {
+                    let value = convert_value(i32::from(*offset), grid, new_grid, "corner")?;
+                    *offset = i16::try_from(value).map_err(|_| ParseError::Overflow("corner"))?;
+                }
+            }
+        }
+        self.grid = new_grid;
+        for child in &mut self.children {
+            child.regrid_from(grid, new_grid)?;
+        }
+        Ok(())
+    }
+
+    /// 沿 `axis` 以 `pivot`（本组精度单位）为镜面镜像本组及所有子组，即 `v' = 2 * pivot - v`，
+    /// 子组按各自精度换算镜面位置；变换盒的角点偏移与翻转标记一并调整。出错时不做任何修改
+    pub fn mirror(&mut self, axis: Axis, pivot: i32) -> Result<(), ParseError> {
+        let reflect = pivot.checked_mul(2).ok_or(ParseError::Overflow("pivot"))?;
+        let mut mirrored = self.clone();
+        mirrored.reflect(axis, reflect)?;
+        *self = mirrored;
+        Ok(())
+    }
+
+    fn reflect(&mut self, axis: Axis, reflect: i32) -> Result<(), ParseError> {
+        for tile in self
+            .tiles
+            .values_mut()
+            .flat_map(|c| c.values_mut())
+            .flatten()
+        {
+            *tile = tile
+                .mirrored(axis, reflect)
+                .ok_or(ParseError::Overflow("tile"))?;
+        }
+        for child in &mut self.children {
+            let child_reflect = convert_value(reflect, self.grid, child.grid, "pivot")?;
+            child.reflect(axis, child_reflect)?;
+        }
+        Ok(())
+    }
+
+    /// 递归地把与本组精度相同的平凡子组并入本组：双方都没有结构信息（`s`），
+    /// 或子组为固定结构（`fixed`）而本组没有结构或同为固定结构。
+    /// 小方块按材质与颜色合并，子组的子组接到本组下。扩展数据（`e`）须为空或与本组相同
+    pub fn coalesce(&mut self) {
+        for mut child in std::mem::take(&mut self.children) {
+            child.coalesce();
+            let trivial = matches!(
+                (&child.structure, &self.structure),
+                (None, None)
+                    | (
+                        Some(LittleStructure::Fixed),
+                        None | Some(LittleStructure::Fixed)
+                    )
+            );
+            let mergeable = child.grid == self.grid
+                && trivial
+                && (child.extension.is_none() || child.extension == self.extension);
+            if !mergeable {
+                self.children.push(child);
+                continue;
+            }
+            for (mat, color_tiles) in child.tiles {
+                let merged = self.tiles.entry(mat).or_default();
+                for (color, tiles) in color_tiles {
+                    merged.entry(color).or_default().extend(tiles);
+                }
+            }
+            self.children.append(&mut child.children);
+        }
+    }
+
+    fn shift(&mut self, delta: LittlePos) -> Result<(), ParseError> {
+        for color_tiles in self.tiles.values_mut() {
+            for tile in color_tiles.values_mut().flatten() {
+                let (min_pos, max_pos) = tile.bounds_mut();
+                *min_pos = min_pos
+                    .checked_add(delta)
+                    .ok_or(ParseError::Overflow("tile"))?;
+                *max_pos = max_pos
+                    .checked_add(delta)
+                    .ok_or(ParseError::Overflow("tile"))?;
+            }
+        }
+        for child in &mut self.children {
+            let child_delta = convert_delta(delta, self.grid, child.grid)?;
+            child.shift(child_delta)?;
+        }
+        Ok(())
+    }
+}
+
+impl LittleBlueprint {
+    /// 沿 `axis` 以 `pivot`（顶层精度单位）为镜面镜像整个作品，随后重新计算包围盒
+    pub fn mirror(&mut self, axis: Axis, pivot: i32) -> Result<(), ParseError> {
+        self.top_group.mirror(axis, pivot)?;
+        self.recompute_bounds();
+        Ok(())
+    }
+
+    /// 平移整个作品，`delta` 以顶层精度为单位，包围盒一并平移；出错时不做任何修改
+    pub fn translate(&mut self, delta: LittlePos) -> Result<(), ParseError> {
+        let min_pos = self
+            .min_pos
+            .checked_add(delta)
+            .ok_or(ParseError::Overflow("min"))?;
+        let max_pos = self
+            .max_pos
+            .checked_add(delta)
+            .ok_or(ParseError::Overflow("size"))?;
+        self.top_group.translate(delta)?;
+        self.min_pos = min_pos;
+        self.max_pos = max_pos;
+        Ok(())
+    }
+
+    /// 将整个作品放大 `factor` 倍（每个单元变为 `factor³` 个单元），精度保持不变。
+    /// 小方块坐标、变换盒角点偏移与蓝图包围盒一并缩放；`factor` 为 0 或任何一处溢出都返回错误且不做修改
+    pub fn upscale(&mut self, factor: u32) -> Result<(), ParseError> {
+        if factor == 0 {
+            return Err(ParseError::OutOfRange("factor"));
+        }
+        let factor = i32::try_from(factor).map_err(|_| ParseError::Overflow("factor"))?;
+        let mut top_group = self.top_group.clone();
+        for (_, _, tile) in top_group.iter_tiles_mut() {
+            *tile = tile
+                .checked_scale(factor)
+                .ok_or(ParseError::Overflow("tile"))?;
+        }
+        let min_pos = self
+            .min_pos
+            .checked_scale(factor)
+            .ok_or(ParseError::Overflow("min"))?;
+        let max_pos = self
+            .max_pos
+            .checked_scale(factor)
+            .ok_or(ParseError::Overflow("size"))?;
+
+        self.top_group = top_group;
+        self.min_pos = min_pos;
+        self.max_pos = max_pos;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_data::sample_blueprint;
+    use crate::{Axis, LittleGroup, LittlePos, LittleTile, ParseError};
+    use quartz_nbt::{NbtCompound, snbt};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_upscale() {
+        let mut blueprint = sample_blueprint();
+        let before: Vec<_> = blueprint
+            .top_group
+            .iter_tiles_mut()
+            .map(|(_, _, tile)| tile.bounds())
+            .collect();
+        blueprint.upscale(2).expect("Failed to upscale");
+        let after: Vec<_> = blueprint
+            .top_group
+            .iter_tiles_mut()
+            .map(|(_, _, tile)| tile.bounds())
+            .collect();
+        for ((min, max), (min2, max2)) in before.iter().zip(&after) {
+            assert_eq!(max2.x - min2.x, 2 * (max.x - min.x));
+            assert_eq!(max2.y - min2.y, 2 * (max.y - min.y));
+            assert_eq!(max2.z - min2.z, 2 * (max.z - min.z));
+            assert_eq!(min2.x, 2 * min.x);
+        }
+        assert_eq!(blueprint.max_pos.x - blueprint.min_pos.x, 10);
+        assert_eq!(blueprint.top_group.grid, 4);
+    }
+
+    #[test]
+    fn test_upscale_overflow_is_atomic() {
+        let mut blueprint = sample_blueprint();
+        let huge = LittleTile::try_from(vec![0, 0, 0, 1, 1, i32::MAX / 2]).unwrap();
+        let tiles = blueprint
+            .top_group
+            .tiles
+            .get_mut("minecraft:white_wool")
+            .unwrap();
+        tiles.values_mut().next().unwrap().push(huge);
+        let expected = blueprint.clone();
+        assert!(blueprint.upscale(4).is_err());
+        assert_eq!(blueprint, expected);
+
+        assert!(matches!(
+            blueprint.upscale(0),
+            Err(ParseError::OutOfRange("factor"))
+        ));
+        assert_eq!(blueprint, expected);
+    }
+
+    #[test]
+    fn test_translate_blueprint() {
+        let mut blueprint = sample_blueprint();
+        let delta = LittlePos { x: 4, y: -2, z: 8 };
+        blueprint.translate(delta).unwrap();
+        assert_eq!(blueprint.min_pos, LittlePos { x: 4, y: -2, z: 11 });
+        assert_eq!(blueprint.max_pos, LittlePos { x: 9, y: -1, z: 16 });
+        let stone = &blueprint.top_group.children[0].tiles["minecraft:stone"];
+        assert_eq!(
+            stone.values().next().unwrap()[0].bounds().0,
+            LittlePos { x: 7, y: -2, z: 11 }
+        );
+
+        let root: NbtCompound = blueprint.clone().try_into().unwrap();
+        let parsed = crate::LittleBlueprint::try_from(root).unwrap();
+        assert_eq!(parsed, blueprint);
+
+        let expected = blueprint.clone();
+        let far = LittlePos {
+            x: i32::MAX,
+            y: 0,
+            z: 0,
+        };
+        assert!(blueprint.translate(far).is_err());
+        assert_eq!(blueprint, expected);
+    }
+
+    #[test]
+    fn test_regrid() {
+        let mut group = sample_blueprint().top_group;
+        let original = group.clone();
+        group.regrid(8).unwrap();
+        assert_eq!(group.grid, 8);
+        let white = crate::LittleColor::try_from(-1).unwrap();
+        assert_eq!(
+            group.tiles["minecraft:white_wool"][&white][0].bounds(),
+            (
+                LittlePos { x: 6, y: 0, z: 14 },
+                LittlePos { x: 8, y: 2, z: 16 }
+            )
+        );
+        let lime = &group.children[1].children[0].children[0];
+        assert_eq!(lime.grid, 8);
+
+        group.regrid(4).unwrap();
+        assert_eq!(group, original);
+
+        // 奇数坐标无法变粗，且不做任何修改
+        assert!(matches!(
+            group.regrid(2),
+            Err(ParseError::IncompatibleGrid { grid: 4, target: 2 })
+        ));
+        assert_eq!(group, original);
+    }
+
+    #[test]
+    fn test_mirror() {
+        let mut blueprint = sample_blueprint();
+        let original = blueprint.clone();
+        blueprint.mirror(Axis::X, 0).unwrap();
+        assert_eq!(blueprint.min_pos, LittlePos { x: -5, y: 0, z: 3 });
+        assert_eq!(blueprint.max_pos, LittlePos { x: 0, y: 1, z: 8 });
+        let white = crate::LittleColor::try_from(-1).unwrap();
+        assert_eq!(
+            blueprint.top_group.tiles["minecraft:white_wool"][&white][0].bounds(),
+            (
+                LittlePos { x: -4, y: 0, z: 7 },
+                LittlePos { x: -3, y: 1, z: 8 }
+            )
+        );
+        blueprint.mirror(Axis::X, 0).unwrap();
+        assert_eq!(blueprint, original);
+
+        // 子组精度不同时按比例换算镜面
+        let mut group = original.top_group.clone();
+        group.children[0].regrid(8).unwrap();
+        group.mirror(Axis::Z, 5).unwrap();
+        let stone = &group.children[0].tiles["minecraft:stone"][&white];
+        assert_eq!(stone[0].bounds().0.z, 2 * (10 - 4));
+        assert!(group.mirror(Axis::Y, i32::MAX).is_err());
+    }
+
+    #[test]
+    fn test_from_anisotropic() {
+        let white = crate::LittleColor::try_from(-1).unwrap();
+        let tile = LittleTile::try_from(vec![1, 2, 3, 2, 4, 7]).unwrap();
+        let tiles = HashMap::from([(
+            "minecraft:stone".to_string(),
+            HashMap::from([(white, vec![tile])]),
+        )]);
+        let group = LittleGroup::from_anisotropic(tiles, 4, 4, 8).unwrap();
+        assert_eq!(group.grid, 8);
+        // X、Y 放大两倍，Z 保持不变
+        let converted = &group.tiles["minecraft:stone"][&white][0];
+        assert_eq!(
+            converted.bounds(),
+            (
+                LittlePos { x: 2, y: 4, z: 3 },
+                LittlePos { x: 4, y: 8, z: 7 }
+            )
+        );
+
+        assert!(matches!(
+            LittleGroup::from_anisotropic(HashMap::new(), 4, 0, 8),
+            Err(ParseError::OutOfRange("grid"))
+        ));
+        assert!(matches!(
+            LittleGroup::from_anisotropic(HashMap::new(), 255, 256, 257),
+            Err(ParseError::Overflow("grid"))
+        ));
+        // 中间结果超出 u32 也只报错，不会 panic
+        assert!(matches!(
+            LittleGroup::from_anisotropic(HashMap::new(), 65535, 65534, 65533),
+            Err(ParseError::Overflow("grid"))
+        ));
+    }
+
+    #[test]
+    fn test_coalesce() {
+        let nbt: NbtCompound = snbt::parse(
+            r#"{
+            grid: 4,
+            c: [
+                {
+                    grid: 4,
+                    c: [],
+                    t: {
+                        "minecraft:stone": [[I; -1], [I; 1, 0, 0, 2, 1, 1]],
+                        "minecraft:glass": [[I; -1], [I; 2, 0, 0, 3, 1, 1]]
+                    }
+                },
+                {
+                    grid: 4,
+                    s: { id: "door", open: 0b },
+                    c: [],
+                    t: { "minecraft:stone": [[I; -1], [I; 5, 0, 0, 6, 1, 1]] }
+                }
+            ],
+            t: { "minecraft:stone": [[I; -1], [I; 0, 0, 0, 1, 1, 1]] }
+        }"#,
+        )
+        .unwrap();
+        let mut group = LittleGroup::try_from(nbt).unwrap();
+        let tiles = group.iter_tiles().count();
+        group.coalesce();
+        assert_eq!(group.iter_tiles().count(), tiles);
+        // 普通子组被并入，带非固定结构的子组保留
+        assert_eq!(group.children.len(), 1);
+        assert_eq!(group.children[0].structure.as_ref().unwrap().id(), "door");
+        let white = crate::LittleColor::try_from(-1).unwrap();
+        assert_eq!(group.tiles["minecraft:stone"][&white].len(), 2);
+        assert_eq!(group.tiles["minecraft:glass"][&white].len(), 1);
+
+        // 示例蓝图的子组都是固定结构，逐层并入没有结构的顶层组
+        let mut top = sample_blueprint().top_group;
+        let tiles = top.iter_tiles().count();
+        top.coalesce();
+        assert!(top.children.is_empty());
+        assert!(top.structure.is_none());
+        assert_eq!(top.iter_tiles().count(), tiles);
+        assert_eq!(top.tiles["minecraft:stone"][&white].len(), 4);
+        assert_eq!(top.tiles["minecraft:lime_wool"][&white].len(), 1);
+
+        // 固定结构的父组同样可以吸收固定结构的子组，但不吸收没有结构的子组
+        let mut fixed = sample_blueprint().top_group.children.remove(1);
+        fixed.children[0].children.push(
+            LittleGroup::try_from(snbt::parse("{ grid: 4, c: [], t: {} }").unwrap()).unwrap(),
+        );
+        fixed.coalesce();
+        assert_eq!(fixed.children.len(), 1);
+        assert!(fixed.children[0].structure.is_none());
+        assert_eq!(fixed.iter_tiles().count(), 3);
+    }
+}
@@ -0,0 +1,678 @@
+use crate::{
+    Axis, LittleBlueprint, LittleColor, LittleGroup, LittlePos, LittleTile, MaterialTiles,
+    OccupancyMode, ParseError, greedy_mesh_optimal,
+};
+use std::collections::{BTreeMap, HashMap};
+
+/// MagicaVoxel 单个模型每个轴最多 256 个体素，超出时拆分为多个模型
+const VOX_MODEL_SIZE: i32 = 256;
+/// 调色板下标 0 表示空，可用颜色为 1..=255
+const VOX_PALETTE_SIZE: usize = 255;
+
+fn push_i32(out: &mut Vec<u8>, value: i32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn push_string(out: &mut Vec<u8>, value: &str) {
+    push_i32(out, value.len() as i32);
+    out.extend_from_slice(value.as_bytes());
+}
+
+fn push_dict(out: &mut Vec<u8>, pairs: &[(&str, &str)]) {
+    push_i32(out, pairs.len() as i32);
+    for (key, value) in pairs {
+        push_string(out, key);
+        push_string(out, value);
+    }
+}
+
+/// 写出一个块：4 字节标识、内容长度、子块长度、内容、子块
+fn push_chunk(out: &mut Vec<u8>, id: &[u8; 4], content: &[u8], children: &[u8]) {
+    out.extend_from_slice(id);
+    push_i32(out, content.len() as i32);
+    push_i32(out, children.len() as i32);
+    out.extend_from_slice(content);
+    out.extend_from_slice(children);
+}
+
+fn color_distance(a: LittleColor, b: LittleColor) -> u32 {
+    [(a.r, b.r), (a.g, b.g), (a.b, b.b), (a.a, b.a)]
+        .into_iter()
+        .map(|(x, y)| u32::from(x.abs_diff(y)).pow(2))
+        .sum()
+}
+
+/// 未提供 `RGBA` 块时使用的 MagicaVoxel 默认调色板，下标 `i` 对应颜色索引 `i + 1`：
+/// 先是六级（FF、CC、99、66、33、00）的 RGB 立方体（不含黑色），再是红、绿、蓝、灰各十级的渐变
+fn default_palette() -> Vec<LittleColor> {
+    const CUBE: [u8; 6] = [0xFF, 0xCC, 0x99, 0x66, 0x33, 0x00];
+    const RAMP: [u8; 10] = [0xEE, 0xDD, 0xBB, 0xAA, 0x88, 0x77, 0x55, 0x44, 0x22, 0x11];
+    let opaque = |r, g, b| LittleColor { r, g, b, a: 255 };
+    let mut palette = Vec::with_capacity(256);
+    for r in CUBE {
+        for g in CUBE {
+            for b in CUBE {
+                palette.push(opaque(r, g, b));
+            }
+        }
+    }
+    palette.pop();
+    for ramp in 0..4 {
+        for v in RAMP {
+            palette.push(match ramp {
+                0 => opaque(v, 0, 0),
+                1 => opaque(0, v, 0),
+                2 => opaque(0, 0, v),
+                _ => opaque(v, v, v),
+            });
+        }
+    }
+    palette.push(LittleColor::default());
+    palette
+}
+
+/// 小端读取器，越界时返回 `InvalidFormat`
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], ParseError> {
+        if self.bytes.len() < n {
+            return Err(ParseError::InvalidFormat);
+        }
+        let (head, rest) = self.bytes.split_at(n);
+        self.bytes = rest;
+        Ok(head)
+    }
+
+    fn i32(&mut self) -> Result<i32, ParseError> {
+        Ok(i32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn len(&mut self) -> Result<usize, ParseError> {
+        usize::try_from(self.i32()?).map_err(|_| ParseError::InvalidFormat)
+    }
+
+    fn string(&mut self) -> Result<String, ParseError> {
+        let len = self.len()?;
+        String::from_utf8(self.take(len)?.to_vec()).map_err(|_| ParseError::InvalidFormat)
+    }
+
+    fn dict(&mut self) -> Result<HashMap<String, String>, ParseError> {
+        (0..self.len()?)
+            .map(|_| Ok((self.string()?, self.string()?)))
+            .collect()
+    }
+}
+
+/// 场景图节点，只保留定位模型所需的信息
+enum SceneNode {
+    Transform { child: i32, translation: [i32; 3] },
+    Group(Vec<i32>),
+    Shape(Vec<i32>),
+}
+
+fn parse_translation(frame: &HashMap<String, String>) -> Result<[i32; 3], ParseError> {
+    let Some(t) = frame.get("_t") else {
+        return Ok([0; 3]);
+    };
+    let values: Vec<i32> = t
+        .split_whitespace()
+        .map(|v| v.parse().map_err(|_| ParseError::InvalidFormat))
+        .collect::<Result<_, _>>()?;
+    values.try_into().map_err(|_| ParseError::InvalidFormat)
+}
+
+/// 从 `node` 出发累加平移，记录每个形状节点引用的 `(模型, 平移)`
+fn place_models(
+    nodes: &HashMap<i32, SceneNode>,
+    node: i32,
+    offset: [i32; 3],
+    depth: usize,
+    out: &mut Vec<(usize, [i32; 3])>,
+) -> Result<(), ParseError> {
+    // 场景图应为树，防止损坏文件中的环导致无限递归
+    if depth > nodes.len() {
+        return Err(ParseError::InvalidFormat);
+    }
+    match nodes.get(&node) {
+        Some(SceneNode::Transform { child, translation }) => {
+            let mut sum = offset;
+            for axis in 0..3 {
+                sum[axis] = sum[axis]
+                    .checked_add(translation[axis])
+                    .ok_or(ParseError::Overflow("translation"))?;
+            }
+            place_models(nodes, *child, sum, depth + 1, out)
+        }
+        Some(SceneNode::Group(children)) => children
+            .iter()
+            .try_for_each(|&child| place_models(nodes, child, offset, depth + 1, out)),
+        Some(SceneNode::Shape(models)) => {
+            for &model in models {
+                out.push((
+                    usize::try_from(model).map_err(|_| ParseError::InvalidFormat)?,
+                    offset,
+                ));
+            }
+            Ok(())
+        }
+        None => Err(ParseError::InvalidFormat),
+    }
+}
+
+impl LittleBlueprint {
+    /// 从 MagicaVoxel `.vox` 文件导入，每个体素对应 `grid` 精度下的一个单元格，
+    /// 所有小方块使用同一材质 `material`，按 256 对齐分块、块内按颜色分组后用
+    /// [`greedy_mesh_optimal`] 合并为盒子。
+    ///
+    /// 坐标约定与 [`LittleBlueprint::to_vox`] 相同；多个模型按场景图中的平移放置
+    /// （忽略旋转），没有场景图时都放在原点。缺少 `RGBA` 块时使用 MagicaVoxel 默认调色板；
+    /// 模型尺寸超出 `1..=256` 时返回 `OutOfRange("size")`
+    pub fn from_vox(bytes: &[u8], grid: u16, material: &str) -> Result<Self, ParseError> {
+        let mut reader = Reader { bytes };
+        if reader.take(4)? != b"VOX " {
+            return Err(ParseError::InvalidFormat);
+        }
+        reader.i32()?;
+        if reader.take(4)? != b"MAIN" {
+            return Err(ParseError::InvalidFormat);
+        }
+        let content_len = reader.len()?;
+        let children_len = reader.len()?;
+        reader.take(content_len)?;
+        let mut reader = Reader {
+            bytes: reader.take(children_len)?,
+        };
+
+        let mut models: Vec<([i32; 3], Vec<[u8; 4]>)> = Vec::new();
+        let mut size = None;
+        let mut palette = default_palette();
+        let mut nodes = HashMap::new();
+        while !reader.bytes.is_empty() {
+            let id = reader.take(4)?;
+            let content_len = reader.len()?;
+            let children_len = reader.len()?;
+            let mut chunk = Reader {
+                bytes: reader.take(content_len)?,
+            };
+            reader.take(children_len)?;
+            match id {
+                b"SIZE" => {
+                    let dims = [chunk.i32()?, chunk.i32()?, chunk.i32()?];
+                    if dims.iter().any(|d| !(1..=VOX_MODEL_SIZE).contains(d)) {
+                        return Err(ParseError::OutOfRange("size"));
+                    }
+                    size = Some(dims);
+                }
+                b"XYZI" => {
+                    let size = size.take().ok_or(ParseError::InvalidFormat)?;
+                    let voxels = (0..chunk.len()?)
+                        .map(|_| Ok(chunk.take(4)?.try_into().unwrap()))
+                        .collect::<Result<_, ParseError>>()?;
+                    models.push((size, voxels));
+                }
+                b"RGBA" => {
+                    palette = (0..256)
+                        .map(|_| {
+                            let [r, g, b, a] = chunk.take(4)?.try_into().unwrap();
+                            Ok(LittleColor { r, g, b, a })
+                        })
+                        .collect::<Result<_, ParseError>>()?;
+                }
+                b"nTRN" => {
+                    let id = chunk.i32()?;
+                    chunk.dict()?;
+                    let child = chunk.i32()?;
+                    chunk.i32()?;
+                    chunk.i32()?;
+                    let translation = match chunk.len()? {
+                        0 => [0; 3],
+                        _ => parse_translation(&chunk.dict()?)?,
+                    };
+                    nodes.insert(id, SceneNode::Transform { child, translation });
+                }
+                b"nGRP" => {
+                    let id = chunk.i32()?;
+                    chunk.dict()?;
+                    let children = (0..chunk.len()?)
+                        .map(|_| chunk.i32())
+                        .collect::<Result<_, _>>()?;
+                    nodes.insert(id, SceneNode::Group(children));
+                }
+                b"nSHP" => {
+                    let id = chunk.i32()?;
+                    chunk.dict()?;
+                    let shapes = (0..chunk.len()?)
+                        .map(|_| {
+                            let model = chunk.i32()?;
+                            chunk.dict()?;
+                            Ok(model)
+                        })
+                        .collect::<Result<_, ParseError>>()?;
+                    nodes.insert(id, SceneNode::Shape(shapes));
+                }
+                // 材质、图层、相机等与几何无关的块直接跳过
+                _ => {}
+            }
+        }
+
+        // 每个放置为 (模型, 模型原点)，场景图平移指向模型中心
+        let mut placements = Vec::new();
+        if nodes.is_empty() {
+            placements.extend((0..models.len()).map(|model| (model, [0; 3])));
+        } else {
+            let mut centers = Vec::new();
+            place_models(&nodes, 0, [0; 3], 0, &mut centers)?;
+            for (model, center) in centers {
+                let (size, _) = models.get(model).ok_or(ParseError::InvalidFormat)?;
+                placements.push((model, [0, 1, 2].map(|axis| center[axis] - size[axis] / 2)));
+            }
+        }
+
+        let mut cells: BTreeMap<LittlePos, LittleColor> = BTreeMap::new();
+        for (model, origin) in placements {
+            for &[x, y, z, index] in &models[model].1 {
+                let voxel = [x, y, z].map(i32::from);
+                let world = [0, 1, 2].map(|axis| origin[axis] + voxel[axis]);
+                let color = palette[usize::from(index).saturating_sub(1)];
+                let pos = LittlePos {
+                    x: world[0],
+                    y: world[2],
+                    z: world[1],
+                };
+                cells.insert(pos, color);
+            }
+        }
+
+        let mut tiles = MaterialTiles::new();
+        let (min_pos, max_pos) = match cells.keys().copied().reduce(LittlePos::component_min) {
+            None => (
+                LittlePos { x: 0, y: 0, z: 0 },
+                LittlePos { x: 0, y: 0, z: 0 },
+            ),
+            Some(min_pos) => {
+                let max_pos = cells
+                    .keys()
+                    .copied()
+                    .fold(min_pos, LittlePos::component_max)
+                    .checked_add(LittlePos { x: 1, y: 1, z: 1 })
+                    .ok_or(ParseError::Overflow("size"))?;
+                // 先确认范围能在 `grid` 下表示
+                let extent = LittleTile::Box { min_pos, max_pos };
+                if !extent.is_valid(grid) {
+                    return Err(ParseError::Overflow("dims"));
+                }
+                // 按模型大小对齐分块、块内再按颜色分组，掩码只覆盖该组单元格的包围盒，
+                // 内存占用与整体范围无关
+                let mut groups: BTreeMap<(LittlePos, LittleColor), Vec<LittlePos>> =
+                    BTreeMap::new();
+                for (&pos, &color) in &cells {
+                    let block = LittlePos {
+                        x: pos.x.div_euclid(VOX_MODEL_SIZE),
+                        y: pos.y.div_euclid(VOX_MODEL_SIZE),
+                        z: pos.z.div_euclid(VOX_MODEL_SIZE),
+                    };
+                    groups.entry((block, color)).or_default().push(pos);
+                }
+                let color_tiles = tiles.entry(material.to_owned()).or_default();
+                for ((_, color), positions) in groups {
+                    let first = positions[0];
+                    let lo = positions
+                        .iter()
+                        .copied()
+                        .fold(first, LittlePos::component_min);
+                    let hi = positions
+                        .iter()
+                        .copied()
+                        .fold(first, LittlePos::component_max);
+                    let dims = Axis::ALL.map(|axis| (hi.get(axis) - lo.get(axis) + 1) as u32);
+                    let mut mask = vec![false; dims.iter().map(|&d| d as usize).product()];
+                    for pos in positions {
+                        let (x, y, z) = (
+                            (pos.x - lo.x) as usize,
+                            (pos.y - lo.y) as usize,
+                            (pos.z - lo.z) as usize,
+                        );
+                        mask[x + dims[0] as usize * (y + dims[1] as usize * z)] = true;
+                    }
+                    let boxes = greedy_mesh_optimal(grid, dims, &mask)?
+                        .into_iter()
+                        .map(|tile| {
+                            let (min, max) = tile.bounds();
+                            Ok(LittleTile::Box {
+                                min_pos: min.checked_add(lo).ok_or(ParseError::Overflow("tile"))?,
+                                max_pos: max.checked_add(lo).ok_or(ParseError::Overflow("tile"))?,
+                            })
+                        })
+                        .collect::<Result<Vec<_>, ParseError>>()?;
+                    color_tiles.entry(color).or_default().extend(boxes);
+                }
+                (min_pos, max_pos)
+            }
+        };
+
+        let boxes_cnt = tiles
+            .values()
+            .flat_map(|c| c.values())
+            .map(Vec::len)
+            .sum::<usize>();
+        let tiles_cnt = tiles.values().map(|c| c.len()).sum::<usize>();
+        Ok(LittleBlueprint {
+            boxes_cnt: boxes_cnt as u32,
+            tiles_cnt: tiles_cnt as u32,
+            min_pos,
+            max_pos,
+            top_group: LittleGroup {
+                grid,
+                children: Vec::new(),
+                tiles,
+                structure: None,
+                extension: None,
+                format: Default::default(),
+            },
+        })
+    }
+
+    /// 导出为 MagicaVoxel `.vox` 文件（版本 150），每个公共精度单元格对应一个体素。
+    ///
+    /// - 坐标由 Y 轴向上换为 Z 轴向上：体素 `(x, y, z)` 对应本库的 `(x, z, y)`
+    /// - 每个模型每轴最多 256 个体素，更大的作品按 256 对齐拆分为多个模型，
+    ///   并通过场景图（`nTRN`/`nGRP`/`nSHP`）的平移还原各自位置
+    /// - 颜色超过 255 种时保留使用最多的 255 种，其余映射到 RGBA 距离最近的颜色
+    pub fn to_vox(&self) -> Result<Vec<u8>, ParseError> {
+        let occupancy = self.occupancy_map(OccupancyMode::Geometric)?;
+        let mut counts: HashMap<LittleColor, usize> = HashMap::new();
+        for &(_, color) in occupancy.cells.values() {
+            *counts.entry(color).or_default() += 1;
+        }
+        let mut ranked: Vec<(LittleColor, usize)> = counts.into_iter().collect();
+        ranked.sort_by_key(|&(color, count)| (std::cmp::Reverse(count), color));
+        let palette: Vec<LittleColor> = ranked
+            .iter()
+            .take(VOX_PALETTE_SIZE)
+            .map(|&(color, _)| color)
+            .collect();
+        let mut index_of: HashMap<LittleColor, u8> = HashMap::new();
+        for &(color, _) in &ranked {
+            let nearest = (0..palette.len())
+                .min_by_key(|&i| color_distance(palette[i], color))
+                .unwrap_or(0);
+            index_of.insert(color, nearest as u8 + 1);
+        }
+
+        // 按 256 对齐分块，键为块坐标（体素坐标系）
+        let mut models: BTreeMap<[i32; 3], Vec<[u8; 4]>> = BTreeMap::new();
+        for (&pos, &(_, color)) in &occupancy.cells {
+            let voxel = [pos.x, pos.z, pos.y];
+            let key = voxel.map(|v| v.div_euclid(VOX_MODEL_SIZE));
+            let local = voxel.map(|v| v.rem_euclid(VOX_MODEL_SIZE) as u8);
+            models
+                .entry(key)
+                .or_default()
+                .push([local[0], local[1], local[2], index_of[&color]]);
+        }
+        // MagicaVoxel 要求至少一个模型
+        if models.is_empty() {
+            models.insert([0; 3], Vec::new());
+        }
+
+        let mut children = Vec::new();
+        let mut sizes = Vec::new();
+        for (key, voxels) in &mut models {
+            voxels.sort();
+            let size = if voxels.is_empty() {
+                [1; 3]
+            } else {
+                [0, 1, 2]
+                    .map(|axis| voxels.iter().map(|v| i32::from(v[axis])).max().unwrap_or(0) + 1)
+            };
+            let mut content = Vec::new();
+            size.iter().for_each(|&s| push_i32(&mut content, s));
+            push_chunk(&mut children, b"SIZE", &content, &[]);
+
+            let mut content = Vec::new();
+            push_i32(&mut content, voxels.len() as i32);
+            voxels.iter().for_each(|v| content.extend_from_slice(v));
+            push_chunk(&mut children, b"XYZI", &content, &[]);
+            sizes.push((*key, size));
+        }
+
+        // 场景图：根变换(0) -> 组(1) -> 每个模型一对 变换(2k+2) -> 形状(2k+3)
+        let mut content = Vec::new();
+        push_i32(&mut content, 0);
+        push_dict(&mut content, &[]);
+        push_i32(&mut content, 1);
+        push_i32(&mut content, -1);
+        push_i32(&mut content, -1);
+        push_i32(&mut content, 1);
+        push_dict(&mut content, &[]);
+        push_chunk(&mut children, b"nTRN", &content, &[]);
+
+        let mut content = Vec::new();
+        push_i32(&mut content, 1);
+        push_dict(&mut content, &[]);
+        push_i32(&mut content, sizes.len() as i32);
+        for k in 0..sizes.len() {
+            push_i32(&mut content, 2 * k as i32 + 2);
+        }
+        push_chunk(&mut children, b"nGRP", &content, &[]);
+
+        for (k, (key, size)) in sizes.iter().enumerate() {
+            // 平移量指向模型中心（尺寸整除 2），导入时据此还原模型原点
+            let translation: Vec<String> = (0..3)
+                .map(|axis| {
+                    key[axis]
+                        .checked_mul(VOX_MODEL_SIZE)
+                        .and_then(|origin| origin.checked_add(size[axis] / 2))
+                        .map(|t| t.to_string())
+                        .ok_or(ParseError::Overflow("translation"))
+                })
+                .collect::<Result<_, _>>()?;
+            let mut content = Vec::new();
+            push_i32(&mut content, 2 * k as i32 + 2);
+            push_dict(&mut content, &[]);
+            push_i32(&mut content, 2 * k as i32 + 3);
+            push_i32(&mut content, -1);
+            push_i32(&mut content, 0);
+            push_i32(&mut content, 1);
+            push_dict(&mut content, &[("_t", &translation.join(" "))]);
+            push_chunk(&mut children, b"nTRN", &content, &[]);
+
+            let mut content = Vec::new();
+            push_i32(&mut content, 2 * k as i32 + 3);
+            push_dict(&mut content, &[]);
+            push_i32(&mut content, 1);
+            push_i32(&mut content, k as i32);
+            push_dict(&mut content, &[]);
+            push_chunk(&mut children, b"nSHP", &content, &[]);
+        }
+
+        // RGBA 块的第 i 项对应调色板下标 i + 1，共 256 项
+        let mut content = Vec::with_capacity(1024);
+        for i in 0..256 {
+            let color = palette.get(i).copied().unwrap_or_default();
+            content.extend_from_slice(&[color.r, color.g, color.b, color.a]);
+        }
+        push_chunk(&mut children, b"RGBA", &content, &[]);
+
+        let mut out = b"VOX ".to_vec();
+        push_i32(&mut out, 150);
+        push_chunk(&mut out, b"MAIN", &[], &children);
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_data::{blueprint_from_snbt, sample_blueprint};
+    use crate::{LittleBlueprint, LittleColor, LittlePos, LittleTile, OccupancyMode, ParseError};
+
+    /// 依次列出 MAIN 下的子块 `(标识, 内容)`
+    fn chunks(bytes: &[u8]) -> Vec<(&[u8], &[u8])> {
+        let read = |at: usize| i32::from_le_bytes(bytes[at..at + 4].try_into().unwrap()) as usize;
+        assert_eq!(&bytes[..4], b"VOX ");
+        assert_eq!(read(4), 150);
+        assert_eq!(&bytes[8..12], b"MAIN");
+        assert_eq!(20 + read(16), bytes.len());
+        let mut at = 20;
+        let mut out = Vec::new();
+        while at < bytes.len() {
+            let len = read(at + 4);
+            out.push((&bytes[at..at + 4], &bytes[at + 12..at + 12 + len]));
+            at += 12 + len + read(at + 8);
+        }
+        out
+    }
+
+    fn voxel_count(bytes: &[u8]) -> usize {
+        chunks(bytes)
+            .into_iter()
+            .filter(|(id, _)| *id == b"XYZI")
+            .map(|(_, content)| i32::from_le_bytes(content[..4].try_into().unwrap()) as usize)
+            .sum()
+    }
+
+    #[test]
+    fn test_to_vox() {
+        let bytes = sample_blueprint().to_vox().unwrap();
+        assert_eq!(voxel_count(&bytes), 8);
+        let parts = chunks(&bytes);
+        let size = parts.iter().find(|(id, _)| *id == b"SIZE").unwrap().1;
+        let size: Vec<i32> = size
+            .chunks(4)
+            .map(|b| i32::from_le_bytes(b.try_into().unwrap()))
+            .collect();
+        // 示例蓝图占据 x 0..5、z 3..8、y 0..1，换为 Z 轴向上
+        assert_eq!(size, vec![5, 8, 1]);
+        let rgba = parts.iter().find(|(id, _)| *id == b"RGBA").unwrap().1;
+        assert_eq!(rgba.len(), 1024);
+        assert_eq!(&rgba[..8], &[255, 255, 255, 255, 0, 0, 0, 0]);
+
+        // 超过 256 个单元格的方向拆分为两个模型
+        let long = blueprint_from_snbt(
+            r#"
+    {
+        min: [I; 0, 0, 0],
+        size: [I; 300, 1, 1],
+        boxes: 1,
+        tiles: 1,
+        grid: 1,
+        c: [],
+        t: {
+            "minecraft:stone": [
+                [I; -1],
+                [I; 0, 0, 0, 300, 1, 1]
+            ]
+        }
+    }
+        "#,
+        );
+        let bytes = long.to_vox().unwrap();
+        assert_eq!(voxel_count(&bytes), 300);
+        let models = chunks(&bytes)
+            .into_iter()
+            .filter(|(id, _)| *id == b"SIZE")
+            .count();
+        assert_eq!(models, 2);
+    }
+
+    #[test]
+    fn test_default_palette() {
+        let palette = super::default_palette();
+        assert_eq!(palette.len(), 256);
+        assert_eq!(palette[0], LittleColor::try_from(-1).unwrap());
+        assert_eq!(
+            palette[254],
+            LittleColor {
+                r: 0x11,
+                g: 0x11,
+                b: 0x11,
+                a: 255
+            }
+        );
+    }
+
+    #[test]
+    fn test_from_vox_roundtrip() {
+        let original = sample_blueprint();
+        let bytes = original.to_vox().unwrap();
+        let imported = LittleBlueprint::from_vox(&bytes, 4, "minecraft:white_wool").unwrap();
+        let cells = |b: &LittleBlueprint| {
+            let mut cells: Vec<_> = b
+                .occupancy_map(OccupancyMode::Geometric)
+                .unwrap()
+                .cells
+                .into_iter()
+                .map(|(pos, (_, c))| (pos, c))
+                .collect();
+            cells.sort();
+            cells
+        };
+        assert_eq!(cells(&imported), cells(&original));
+        assert_eq!(imported.min_pos, LittlePos { x: 0, y: 0, z: 3 });
+        assert_eq!(imported.max_pos, LittlePos { x: 5, y: 1, z: 8 });
+
+        // 拆分为多个模型后仍能按平移还原位置，且颜色保持
+        let red = LittleColor::try_from(-16776961).unwrap();
+        let mut long = blueprint_from_snbt(
+            r#"
+    {
+        min: [I; -20, 0, 0],
+        size: [I; 300, 2, 1],
+        boxes: 1,
+        tiles: 1,
+        grid: 1,
+        c: [],
+        t: {
+            "minecraft:stone": [
+                [I; -1],
+                [I; -20, 0, 0, 280, 1, 1]
+            ]
+        }
+    }
+        "#,
+        );
+        let tile = LittleTile::try_from(vec![5, 1, 0, 6, 2, 1]).unwrap();
+        long.top_group
+            .tiles
+            .get_mut("minecraft:stone")
+            .unwrap()
+            .insert(red, vec![tile]);
+        let imported =
+            LittleBlueprint::from_vox(&long.to_vox().unwrap(), 1, "minecraft:stone").unwrap();
+        assert_eq!(cells(&imported), cells(&long));
+        assert_eq!(imported.top_group.tiles["minecraft:stone"].len(), 2);
+
+        assert!(LittleBlueprint::from_vox(b"VOX ", 4, "minecraft:stone").is_err());
+
+        // 相距很远的体素不会按整体范围分配稠密掩码
+        let mut sparse = long.clone();
+        sparse.top_group.grid = 16;
+        sparse.top_group.tiles.insert(
+            "minecraft:stone".to_string(),
+            [(
+                red,
+                vec![
+                    LittleTile::try_from(vec![0, 0, 0, 1, 1, 1]).unwrap(),
+                    LittleTile::try_from(vec![12000, 12000, 12000, 12001, 12001, 12001]).unwrap(),
+                ],
+            )]
+            .into(),
+        );
+        let imported =
+            LittleBlueprint::from_vox(&sparse.to_vox().unwrap(), 16, "minecraft:stone").unwrap();
+        assert_eq!(cells(&imported), cells(&sparse));
+        assert_eq!(imported.boxes_cnt, 2);
+
+        // 模型尺寸超出 256 的文件直接拒绝
+        let mut bytes = original.to_vox().unwrap();
+        let size = bytes.windows(4).position(|id| id == b"SIZE").unwrap();
+        bytes[size + 12..size + 16].copy_from_slice(&100_000i32.to_le_bytes());
+        assert!(matches!(
+            LittleBlueprint::from_vox(&bytes, 4, "minecraft:stone"),
+            Err(ParseError::OutOfRange("size"))
+        ));
+    }
+}